@@ -1,10 +1,39 @@
 #![allow(dead_code)]
 use zvariant::Type;
 
-#[cfg_attr(test, zbus_lockstep::validate(xml: "xml"))]
+// The XML directory, the interface and the signature policy are taken from
+// `[package.metadata.lockstep]` in `Cargo.toml`.
+#[cfg_attr(test, zbus_lockstep::validate)]
 #[derive(Type)]
 pub struct Alert {
     urgent: bool,
     color: String,
     volume: f64,
 }
+
+// `level` is a `u` in the XML, which the configured policy accepts.
+#[cfg_attr(test, zbus_lockstep::validate)]
+#[derive(Type)]
+pub struct Volume(i32);
+
+#[cfg(test)]
+mod test {
+    use zbus_lockstep::{assert_signature_eq, signal_body_type_signature};
+    use zvariant::Type;
+
+    use super::Volume;
+
+    #[test]
+    fn test_lookup_uses_configured_interface() {
+        assert_eq!(signal_body_type_signature!("Volume"), "u");
+    }
+
+    #[test]
+    fn test_assertion_uses_configured_policy() {
+        assert_signature_eq!(
+            signal_body_type_signature!("Volume"),
+            Volume::SIGNATURE,
+            &[]
+        );
+    }
+}
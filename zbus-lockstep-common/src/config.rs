@@ -0,0 +1,413 @@
+//! The crate-wide configuration, from `lockstep.toml` or `[package.metadata.lockstep]`.
+//!
+//! `zbus-lockstep` reads it at run time and `zbus-lockstep-macros` at compile time, both through
+//! [`Config::load`], so that they accept and reject the same configurations.
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use toml::{Table, Value};
+
+/// The configuration file next to `Cargo.toml`, which takes precedence over the metadata table.
+pub const CONFIG_FILE: &str = "lockstep.toml";
+
+/// The keys a configuration may have.
+const KEYS: [&str; 8] = [
+    "xml",
+    "interface",
+    "naming",
+    "suffixes",
+    "shape",
+    "policy",
+    "include",
+    "exclude",
+];
+
+/// The settings of a crate, as read from its configuration. All are optional.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Config {
+    /// The file the configuration was read from, if any.
+    pub source: Option<PathBuf>,
+    /// The XML definitions directories, resolved against the crate root.
+    pub xml: Vec<PathBuf>,
+    /// The interface of members looked up without one.
+    pub interface: Option<String>,
+    /// The strategy `validate` infers member names with.
+    pub naming: Option<Naming>,
+    /// The suffixes some naming strategies strip.
+    pub suffixes: Option<Vec<String>>,
+    /// How arguments are combined into a signature.
+    pub shape: Option<Shape>,
+    /// The differences between signatures to accept, any of which is accepted.
+    pub policy: Option<Vec<Policy>>,
+    /// The patterns of the XML files to read.
+    pub include: Option<Vec<String>>,
+    /// The patterns of the XML files to skip.
+    pub exclude: Option<Vec<String>>,
+}
+
+/// The configuration at `path` is malformed, or could not be read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError {
+    pub path: PathBuf,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Invalid configuration in \"{}\": {}",
+            self.path.display(),
+            self.message
+        )
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Config {
+    /// Read the configuration of the crate in `manifest_dir`.
+    ///
+    /// Returns `None` if the crate has neither a `lockstep.toml` nor a
+    /// `[package.metadata.lockstep]` table.
+    pub fn load(manifest_dir: impl AsRef<Path>) -> Result<Option<Config>, ConfigError> {
+        let manifest_dir = manifest_dir.as_ref();
+
+        let config_file = manifest_dir.join(CONFIG_FILE);
+        if config_file.is_file() {
+            let table = read_table(&config_file)?;
+            return Config::from_table(&table, manifest_dir, &config_file).map(Some);
+        }
+
+        let manifest = manifest_dir.join("Cargo.toml");
+        if !manifest.is_file() {
+            return Ok(None);
+        }
+        let table = read_table(&manifest)?;
+        match table
+            .get("package")
+            .and_then(|package| package.get("metadata"))
+            .and_then(|metadata| metadata.get("lockstep"))
+        {
+            None => Ok(None),
+            Some(Value::Table(metadata)) => {
+                Config::from_table(metadata, manifest_dir, &manifest).map(Some)
+            }
+            Some(_) => Err(config_error(
+                &manifest,
+                "`package.metadata.lockstep` should be a table",
+            )),
+        }
+    }
+
+    /// The configuration of the crate being built or tested, found through `CARGO_MANIFEST_DIR`.
+    ///
+    /// It is read once and shared by all callers in the process, which is only right at run time:
+    /// a compiler process may expand the macros of several crates.
+    pub fn shared() -> Result<Option<&'static Config>, &'static ConfigError> {
+        static SHARED: OnceLock<Result<Option<Config>, ConfigError>> = OnceLock::new();
+
+        SHARED
+            .get_or_init(|| match std::env::var("CARGO_MANIFEST_DIR") {
+                Ok(manifest_dir) => Config::load(manifest_dir),
+                Err(_) => Ok(None),
+            })
+            .as_ref()
+            .map(Option::as_ref)
+    }
+
+    fn from_table(
+        table: &Table,
+        manifest_dir: &Path,
+        source: &Path,
+    ) -> Result<Config, ConfigError> {
+        if let Some(key) = table.keys().find(|key| !KEYS.contains(&key.as_str())) {
+            return Err(config_error(
+                source,
+                format!("unknown key `{key}`, expected one of {}", KEYS.join(", ")),
+            ));
+        }
+
+        let strings = |key: &str| -> Result<Option<Vec<String>>, ConfigError> {
+            match table.get(key) {
+                None => Ok(None),
+                Some(Value::String(value)) => Ok(Some(vec![value.clone()])),
+                Some(Value::Array(values)) => values
+                    .iter()
+                    .map(|value| value.as_str().map(ToOwned::to_owned))
+                    .collect::<Option<Vec<_>>>()
+                    .map(Some)
+                    .ok_or_else(|| config_error(source, format!("`{key}` should list strings"))),
+                Some(_) => Err(config_error(
+                    source,
+                    format!("`{key}` should be a string or a list of strings"),
+                )),
+            }
+        };
+        let string = |key: &str| -> Result<Option<String>, ConfigError> {
+            match table.get(key) {
+                None => Ok(None),
+                Some(Value::String(value)) => Ok(Some(value.clone())),
+                Some(_) => Err(config_error(source, format!("`{key}` should be a string"))),
+            }
+        };
+
+        let naming = string("naming")?
+            .map(|name| named(source, "naming", &name, Naming::ALL, Naming::name))
+            .transpose()?;
+        let shape = string("shape")?
+            .map(|name| named(source, "shape", &name, Shape::ALL, Shape::name))
+            .transpose()?;
+        let policy = strings("policy")?
+            .map(|names| {
+                names
+                    .iter()
+                    .map(|name| named(source, "policy", name, Policy::ALL, Policy::name))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?;
+
+        Ok(Config {
+            source: Some(source.to_owned()),
+            xml: strings("xml")?
+                .unwrap_or_default()
+                .iter()
+                .map(|root| manifest_dir.join(root))
+                .collect(),
+            interface: string("interface")?,
+            naming,
+            suffixes: strings("suffixes")?,
+            shape,
+            policy,
+            include: strings("include")?,
+            exclude: strings("exclude")?,
+        })
+    }
+}
+
+/// How an item's name is matched to the names of XML members.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Naming {
+    /// The item's name contains the member's name, `AddNodeEvent` matches `AddNode`.
+    Contains,
+    /// The item's name is the member's name.
+    Exact,
+    /// The item's name, without one of the suffixes, is the member's name: `AddNodeEvent`
+    /// matches `AddNode`, but `TextChangedEvent` does not match `Changed`.
+    StripSuffix,
+    /// Of the members whose names the item's name contains, the one with the longest name:
+    /// `TextChangedEvent` matches `TextChanged` rather than `Changed`.
+    Longest,
+    /// The member's `snake_case` name, in `CamelCase`, is the item's name without one of the
+    /// suffixes: `ObjectAddedEvent` matches `object_added`.
+    SnakeCase,
+}
+
+impl Naming {
+    pub const ALL: [Naming; 5] = [
+        Naming::Contains,
+        Naming::Exact,
+        Naming::StripSuffix,
+        Naming::Longest,
+        Naming::SnakeCase,
+    ];
+
+    /// The name of the strategy, as it is written in attributes and configurations.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Naming::Contains => "contains",
+            Naming::Exact => "exact",
+            Naming::StripSuffix => "strip_suffix",
+            Naming::Longest => "longest",
+            Naming::SnakeCase => "snake_case",
+        }
+    }
+}
+
+/// How the arguments of a member are combined into a signature.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Shape {
+    Flattened,
+    Struct,
+    Auto,
+}
+
+impl Shape {
+    pub const ALL: [Shape; 3] = [Shape::Flattened, Shape::Struct, Shape::Auto];
+
+    /// The name of the shape, as it is written in attributes and configurations.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Shape::Flattened => "flattened",
+            Shape::Struct => "struct",
+            Shape::Auto => "auto",
+        }
+    }
+}
+
+/// A difference between signatures to accept, one of the built-in `SignaturePolicy` variants.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Policy {
+    Exact,
+    AllowSignDifference,
+    AllowVariantWidening,
+}
+
+impl Policy {
+    pub const ALL: [Policy; 3] = [
+        Policy::Exact,
+        Policy::AllowSignDifference,
+        Policy::AllowVariantWidening,
+    ];
+
+    /// The name of the policy, as it is written in configurations.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Policy::Exact => "exact",
+            Policy::AllowSignDifference => "allow_sign_difference",
+            Policy::AllowVariantWidening => "allow_variant_widening",
+        }
+    }
+}
+
+/// The one of `all` that is called `name`, the value of `key` in the configuration at `source`.
+fn named<T: Copy, const N: usize>(
+    source: &Path,
+    key: &str,
+    name: &str,
+    all: [T; N],
+    name_of: fn(T) -> &'static str,
+) -> Result<T, ConfigError> {
+    all.into_iter()
+        .find(|variant| name_of(*variant) == name)
+        .ok_or_else(|| {
+            let names = all.map(name_of);
+            config_error(
+                source,
+                format!(
+                    "unexpected {key} `{name}`, expected one of {}",
+                    names.join(", ")
+                ),
+            )
+        })
+}
+
+fn read_table(path: &Path) -> Result<Table, ConfigError> {
+    std::fs::read_to_string(path)
+        .map_err(|e| config_error(path, e.to_string()))?
+        .parse::<Table>()
+        .map_err(|e| config_error(path, e.to_string()))
+}
+
+fn config_error(path: &Path, message: impl Into<String>) -> ConfigError {
+    ConfigError {
+        path: path.to_owned(),
+        message: message.into(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_config_from_metadata_table() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            r#"
+[package]
+name = "user"
+
+[package.metadata.lockstep]
+xml = ["xml", "vendor/portal"]
+interface = "org.example.Node"
+naming = "strip_suffix"
+shape = "struct"
+policy = ["allow_sign_difference", "allow_variant_widening"]
+exclude = "**/draft/**"
+"#,
+        )
+        .unwrap();
+
+        let config = Config::load(dir.path()).unwrap().unwrap();
+        assert_eq!(config.source, Some(dir.path().join("Cargo.toml")));
+        assert_eq!(
+            config.xml,
+            [dir.path().join("xml"), dir.path().join("vendor/portal")]
+        );
+        assert_eq!(config.interface.as_deref(), Some("org.example.Node"));
+        assert_eq!(config.naming, Some(Naming::StripSuffix));
+        assert_eq!(config.shape, Some(Shape::Struct));
+        assert_eq!(
+            config.policy,
+            Some(vec![
+                Policy::AllowSignDifference,
+                Policy::AllowVariantWidening
+            ])
+        );
+        assert_eq!(config.exclude, Some(vec!["**/draft/**".to_owned()]));
+        assert_eq!(config.include, None);
+    }
+
+    #[test]
+    fn test_config_file_takes_precedence() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package.metadata.lockstep]\ninterface = \"org.example.Node\"\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join(CONFIG_FILE), "naming = \"exact\"\n").unwrap();
+
+        let config = Config::load(dir.path()).unwrap().unwrap();
+        assert_eq!(config.source, Some(dir.path().join(CONFIG_FILE)));
+        assert_eq!(config.naming, Some(Naming::Exact));
+        assert_eq!(config.interface, None);
+    }
+
+    #[test]
+    fn test_missing_and_malformed_config() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"user\"\n",
+        )
+        .unwrap();
+        assert_eq!(Config::load(dir.path()), Ok(None));
+
+        for (config, message) in [
+            (
+                "shape = \"round\"\n",
+                "unexpected shape `round`, expected one of flattened, struct, auto",
+            ),
+            (
+                "naming = \"fuzzy\"\n",
+                "unexpected naming `fuzzy`, expected one of contains, exact, strip_suffix, \
+                 longest, snake_case",
+            ),
+            (
+                "policy = [\"exact\", \"lenient\"]\n",
+                "unexpected policy `lenient`, expected one of exact, allow_sign_difference, \
+                 allow_variant_widening",
+            ),
+            (
+                "suffixes = 1\n",
+                "`suffixes` should be a string or a list of strings",
+            ),
+            ("colour = \"red\"\n", "unknown key `colour`"),
+        ] {
+            std::fs::write(dir.path().join(CONFIG_FILE), config).unwrap();
+            let err = Config::load(dir.path()).unwrap_err();
+            assert_eq!(err.path, dir.path().join(CONFIG_FILE));
+            assert!(err.message.starts_with(message), "{err}");
+        }
+    }
+}
@@ -0,0 +1,316 @@
+//! Discovery of the XML files in a definitions directory.
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use crate::config::Config;
+
+/// The environment variable with the include patterns of [`XmlFilter::resolve`].
+const INCLUDE_ENV: &str = "LOCKSTEP_XML_INCLUDE";
+
+/// The environment variable with the exclude patterns of [`XmlFilter::resolve`].
+const EXCLUDE_ENV: &str = "LOCKSTEP_XML_EXCLUDE";
+
+/// Which XML files of a definitions directory are read.
+///
+/// Patterns are matched against the path of a file relative to the directory, with `/` as
+/// separator. `*` matches any run of characters within a path segment, `?` a single character and
+/// `**` any number of segments. A pattern without a `/` is matched against the file name alone, at
+/// any depth.
+///
+/// A file is read if it has the `xml` extension, matches one of the include patterns, if there
+/// are any, and none of the exclude patterns.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::path::Path;
+/// use zbus_lockstep_common::discovery::XmlFilter;
+///
+/// let filter = XmlFilter::new()
+///     .include("org/freedesktop/**")
+///     .exclude("*.draft.xml");
+///
+/// assert!(filter.matches(Path::new("org/freedesktop/DBus/Properties.xml")));
+/// assert!(!filter.matches(Path::new("org/freedesktop/Notifications.draft.xml")));
+/// assert!(!filter.matches(Path::new("org/gnome/Shell.xml")));
+/// assert!(!filter.matches(Path::new("org/freedesktop/README")));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct XmlFilter {
+    include: Vec<String>,
+    exclude: Vec<String>,
+}
+
+impl XmlFilter {
+    /// A filter that accepts all XML files.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The filter set by the environment variables `LOCKSTEP_XML_INCLUDE` and
+    /// `LOCKSTEP_XML_EXCLUDE`, each a comma separated list of patterns, each falling back to
+    /// `include` and `exclude`, and then to the keys of the same name in `config`.
+    #[must_use]
+    pub fn resolve(
+        include: Option<Vec<String>>,
+        exclude: Option<Vec<String>>,
+        config: Option<&Config>,
+    ) -> Self {
+        XmlFilter {
+            include: patterns_from_env(INCLUDE_ENV)
+                .or(include)
+                .or_else(|| config.and_then(|config| config.include.clone()))
+                .unwrap_or_default(),
+            exclude: patterns_from_env(EXCLUDE_ENV)
+                .or(exclude)
+                .or_else(|| config.and_then(|config| config.exclude.clone()))
+                .unwrap_or_default(),
+        }
+    }
+
+    /// The filter set by the environment variables, or else by the [shared](Config::shared)
+    /// configuration, see [`XmlFilter::resolve`].
+    #[must_use]
+    pub fn from_env() -> Self {
+        XmlFilter::resolve(None, None, Config::shared().ok().flatten())
+    }
+
+    /// Also read the files matching `pattern`. Without include patterns, all XML files are read.
+    #[must_use]
+    pub fn include(mut self, pattern: impl Into<String>) -> Self {
+        self.include.push(pattern.into());
+        self
+    }
+
+    /// Skip the files matching `pattern`.
+    #[must_use]
+    pub fn exclude(mut self, pattern: impl Into<String>) -> Self {
+        self.exclude.push(pattern.into());
+        self
+    }
+
+    /// Whether the file at `relative_path`, relative to the definitions directory, is read.
+    #[must_use]
+    pub fn matches(&self, relative_path: &Path) -> bool {
+        let is_xml = relative_path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("xml"));
+        let path = relative_path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        is_xml
+            && (self.include.is_empty() || self.include.iter().any(|p| path_match(p, &path)))
+            && !self.exclude.iter().any(|p| path_match(p, &path))
+    }
+}
+
+/// The definitions directory, or one of its subdirectories, at `path` could not be read.
+#[derive(Debug)]
+pub struct DirError {
+    pub path: PathBuf,
+    pub source: std::io::Error,
+}
+
+impl fmt::Display for DirError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Failed to read XML directory \"{}\": {}",
+            self.path.display(),
+            self.source
+        )
+    }
+}
+
+impl std::error::Error for DirError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// The XML files in `dir` and its subdirectories that `filter` accepts.
+///
+/// The files are sorted by path, so that they are always read in the same order and ambiguities
+/// are always reported the same way. Symbolic links to directories are not followed, so that
+/// cycles cannot occur.
+pub fn discover_xml_files(dir: &Path, filter: &XmlFilter) -> Result<Vec<PathBuf>, DirError> {
+    let mut paths = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+
+    while let Some(current) = pending.pop() {
+        let read_error = |source| DirError {
+            path: current.clone(),
+            source,
+        };
+
+        for entry in std::fs::read_dir(&current).map_err(read_error)? {
+            let entry = entry.map_err(read_error)?;
+            let path = entry.path();
+
+            if entry.file_type().map_err(read_error)?.is_dir() {
+                pending.push(path);
+            } else if path.is_file() && filter.matches(path.strip_prefix(dir).unwrap_or(&path)) {
+                paths.push(path);
+            }
+        }
+    }
+    paths.sort();
+
+    Ok(paths)
+}
+
+/// Return whether `name` matches `pattern`.
+///
+/// `*` matches any run of characters, including none, and `?` matches a single character. All
+/// other characters match themselves.
+#[must_use]
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    let (mut p, mut n) = (0, 0);
+    // The position of the last `*` in the pattern, and the position in the name it was tried at.
+    let mut backtrack = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                // Let the last `*` match one more character.
+                Some((star, tried)) => {
+                    backtrack = Some((star, tried + 1));
+                    p = star + 1;
+                    n = tried + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// The patterns of the comma separated list in `var`, if it is set.
+fn patterns_from_env(var: &str) -> Option<Vec<String>> {
+    std::env::var(var).ok().map(|patterns| {
+        patterns
+            .split(',')
+            .map(str::trim)
+            .filter(|pattern| !pattern.is_empty())
+            .map(ToOwned::to_owned)
+            .collect()
+    })
+}
+
+/// Whether the `/` separated `path` matches `pattern`, see [`XmlFilter`].
+fn path_match(pattern: &str, path: &str) -> bool {
+    if !pattern.contains('/') {
+        let file_name = path.rsplit('/').next().unwrap_or(path);
+        return glob_match(pattern, file_name);
+    }
+
+    let pattern: Vec<&str> = pattern.split('/').collect();
+    let path: Vec<&str> = path.split('/').collect();
+    segments_match(&pattern, &path)
+}
+
+fn segments_match(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        // `**` matches any number of segments, including none.
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| segments_match(rest, &path[skip..])),
+        Some((first, rest)) => path.split_first().is_some_and(|(segment, path)| {
+            glob_match(first, segment) && segments_match(rest, path)
+        }),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_path_match() {
+        let table = Path::new(env!("CARGO_MANIFEST_DIR")).join("../xml/path_patterns.txt");
+        let table = std::fs::read_to_string(table).unwrap();
+
+        for line in table
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        {
+            let [pattern, path, expected] = line.split_whitespace().collect::<Vec<_>>()[..] else {
+                panic!("Malformed case: {line}");
+            };
+            assert_eq!(
+                path_match(pattern, path),
+                expected == "match",
+                "{pattern} {path}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_discovery_is_recursive_sorted_and_filtered() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for file in [
+            "b.xml",
+            "a/z.xml",
+            "a/nested/y.xml",
+            "a/nested/y.draft.xml",
+            "README",
+            "a/LICENSE",
+        ] {
+            let path = root.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "<node/>").unwrap();
+        }
+
+        let relative = |filter: &XmlFilter| {
+            discover_xml_files(root, filter)
+                .unwrap()
+                .iter()
+                .map(|path| path.strip_prefix(root).unwrap().to_path_buf())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            relative(&XmlFilter::new()),
+            ["a/nested/y.draft.xml", "a/nested/y.xml", "a/z.xml", "b.xml"].map(PathBuf::from)
+        );
+        assert_eq!(
+            relative(&XmlFilter::new().include("a/**").exclude("*.draft.xml")),
+            ["a/nested/y.xml", "a/z.xml"].map(PathBuf::from)
+        );
+    }
+
+    #[test]
+    fn test_arguments_take_precedence_over_config() {
+        let config = Config {
+            include: Some(vec!["config/**".to_owned()]),
+            exclude: Some(vec!["config.xml".to_owned()]),
+            ..Config::default()
+        };
+
+        let filter = XmlFilter::resolve(Some(vec!["arg/**".to_owned()]), None, Some(&config));
+        assert_eq!(
+            filter,
+            XmlFilter::new().include("arg/**").exclude("config.xml")
+        );
+    }
+}
@@ -0,0 +1,10 @@
+//! # zbus-lockstep-common
+//!
+//! Support code shared by `zbus-lockstep` and `zbus-lockstep-macros`, so that both report the
+//! same names and read the same files. This crate has no stable API of its own.
+#![doc(html_root_url = "https://docs.rs/zbus-lockstep-common/0.6.0")]
+
+pub mod config;
+pub mod discovery;
+pub mod roots;
+pub mod suggest;
@@ -0,0 +1,216 @@
+//! Resolution of the XML definitions directories, the roots.
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use crate::config::Config;
+
+/// The environment variable with the roots, which takes precedence over all other sources.
+const XML_PATH_ENV: &str = "LOCKSTEP_XML_PATH";
+
+/// Where a root came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RootOrigin {
+    /// The `LOCKSTEP_XML_PATH` environment variable.
+    Env,
+    /// The argument at this position.
+    Argument(usize),
+    /// The `xml` key of the configuration.
+    Config,
+    /// One of the default locations.
+    Default,
+}
+
+impl fmt::Display for RootOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            RootOrigin::Env => XML_PATH_ENV,
+            RootOrigin::Argument(_) => "provided XML path",
+            RootOrigin::Config => "configured XML path",
+            RootOrigin::Default => "default XML path",
+        })
+    }
+}
+
+/// The roots could not be resolved.
+#[derive(Debug)]
+pub enum RootError {
+    /// The root at `path`, from `origin`, does not exist or cannot be canonicalized.
+    Canonicalize {
+        origin: RootOrigin,
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// No roots were given, and without `CARGO_MANIFEST_DIR` the default locations are unknown.
+    NoManifestDir,
+    /// No roots were given, and none of the default locations in `manifest_dir` exist.
+    NotFound { manifest_dir: PathBuf },
+}
+
+impl fmt::Display for RootError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RootError::Canonicalize {
+                origin,
+                path,
+                source,
+            } => write!(
+                f,
+                "Failed to canonicalize {origin} \"{}\": {source}",
+                path.display()
+            ),
+            RootError::NoManifestDir => write!(
+                f,
+                "No XML path provided and CARGO_MANIFEST_DIR environment variable is not set"
+            ),
+            RootError::NotFound { manifest_dir } => write!(
+                f,
+                "No XML path provided and default XML path not found. Current directory: \"{}\"",
+                manifest_dir.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RootError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RootError::Canonicalize { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Resolve the roots, canonicalized, from the first of these sources that gives any:
+///
+/// 1. The `LOCKSTEP_XML_PATH` environment variable.
+/// 2. The `args`.
+/// 3. The `xml` roots of `config`.
+/// 4. The first of the default locations that exists: `xml`, `XML`, `../xml`, `../XML`,
+///    `<crate_name>/xml` or `<crate_name>/XML`, relative to `CARGO_MANIFEST_DIR`.
+///
+/// The environment variable and each argument may list several directories, separated like
+/// `PATH`: by `:`, or `;` on Windows. The roots are returned in order, which is the order in which
+/// they are searched. Each must exist.
+pub fn resolve_xml_roots(
+    args: &[String],
+    config: Option<&Config>,
+) -> Result<Vec<PathBuf>, RootError> {
+    if let Ok(env_paths) = std::env::var(XML_PATH_ENV) {
+        return canonicalize_roots(&env_paths, RootOrigin::Env);
+    }
+
+    if !args.is_empty() {
+        let mut roots = Vec::new();
+        for (i, arg) in args.iter().enumerate() {
+            roots.extend(canonicalize_roots(arg, RootOrigin::Argument(i))?);
+        }
+        return Ok(roots);
+    }
+
+    if let Some(config) = config.filter(|config| !config.xml.is_empty()) {
+        return config
+            .xml
+            .iter()
+            .map(|root| canonicalize_root(root, RootOrigin::Config))
+            .collect();
+    }
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .map_err(|_| RootError::NoManifestDir)?;
+    let crate_name = std::env::var("CARGO_PKG_NAME").unwrap_or_else(|_| String::from("unknown"));
+
+    let paths_to_try = [
+        manifest_dir.join("xml"),
+        manifest_dir.join("XML"),
+        manifest_dir.join("../xml"),
+        manifest_dir.join("../XML"),
+        manifest_dir.join(&crate_name).join("xml"),
+        manifest_dir.join(&crate_name).join("XML"),
+    ];
+
+    match paths_to_try.iter().find(|path| path.exists()) {
+        Some(path) => Ok(vec![canonicalize_root(path, RootOrigin::Default)?]),
+        None => Err(RootError::NotFound { manifest_dir }),
+    }
+}
+
+/// Canonicalize each of the `PATH`-style separated `paths`, which came from `origin`.
+fn canonicalize_roots(paths: &str, origin: RootOrigin) -> Result<Vec<PathBuf>, RootError> {
+    std::env::split_paths(paths)
+        .filter(|path| !path.as_os_str().is_empty())
+        .map(|path| canonicalize_root(&path, origin))
+        .collect()
+}
+
+/// Canonicalize `root`, which came from `origin`, naming it should it not exist.
+fn canonicalize_root(root: &Path, origin: RootOrigin) -> Result<PathBuf, RootError> {
+    root.canonicalize()
+        .map_err(|source| RootError::Canonicalize {
+            origin,
+            path: root.to_owned(),
+            source,
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_arguments_are_split_and_kept_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        for root in ["a", "b", "c"] {
+            std::fs::create_dir(dir.path().join(root)).unwrap();
+        }
+        let config = Config {
+            xml: vec![dir.path().join("c")],
+            ..Config::default()
+        };
+
+        let joined = std::env::join_paths([dir.path().join("b"), dir.path().join("a")]).unwrap();
+        let args = [joined.into_string().unwrap()];
+        let roots = resolve_xml_roots(&args, Some(&config)).unwrap();
+        let dir = dir.path().canonicalize().unwrap();
+        assert_eq!(roots, [dir.join("b"), dir.join("a")]);
+
+        assert_eq!(
+            resolve_xml_roots(&[], Some(&config)).unwrap(),
+            [dir.join("c")]
+        );
+    }
+
+    #[test]
+    fn test_missing_root_names_its_origin() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("missing");
+        let args = [
+            dir.path().to_string_lossy().into_owned(),
+            missing.to_string_lossy().into_owned(),
+        ];
+
+        let err = resolve_xml_roots(&args, None).unwrap_err();
+        assert!(
+            matches!(
+                &err,
+                RootError::Canonicalize { origin: RootOrigin::Argument(1), path, .. }
+                    if *path == missing
+            ),
+            "{err}"
+        );
+
+        let config = Config {
+            xml: vec![missing.clone()],
+            ..Config::default()
+        };
+        let err = resolve_xml_roots(&[], Some(&config)).unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("Failed to canonicalize configured XML path"),
+            "{err}"
+        );
+    }
+}
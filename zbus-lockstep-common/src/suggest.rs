@@ -0,0 +1,89 @@
+//! "Did you mean ...?" suggestions for names that were not found.
+
+/// The maximum number of suggestions offered for a single name.
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Return the candidates that are close to `name`, closest first.
+///
+/// A candidate is considered close if it differs only in case, or if its edit distance to `name`
+/// is at most a third of the length of `name` (and at least one).
+pub fn did_you_mean<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Vec<&'a str> {
+    let threshold = (name.chars().count() / 3).max(1);
+    let lowercase_name = name.to_lowercase();
+
+    let mut close: Vec<(usize, &str)> = candidates
+        .into_iter()
+        .filter(|candidate| *candidate != name)
+        .filter_map(|candidate| {
+            if candidate.to_lowercase() == lowercase_name {
+                return Some((0, candidate));
+            }
+            let distance = levenshtein(name, candidate);
+            (distance <= threshold).then_some((distance, candidate))
+        })
+        .collect();
+
+    close.sort_unstable();
+    close.dedup_by(|a, b| a.1 == b.1);
+    close.truncate(MAX_SUGGESTIONS);
+    close.into_iter().map(|(_, candidate)| candidate).collect()
+}
+
+/// Format suggestions as a sentence to append to an error message, or nothing if there are none.
+pub fn format_suggestions(suggestions: &[&str]) -> String {
+    match suggestions {
+        [] => String::new(),
+        [one] => format!(" Did you mean \"{one}\"?"),
+        many => {
+            let quoted: Vec<String> = many.iter().map(|s| format!("\"{s}\"")).collect();
+            format!(" Did you mean one of: {}?", quoted.join(", "))
+        }
+    }
+}
+
+/// The number of single character insertions, deletions or substitutions needed to turn `a`
+/// into `b`.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod test {
+    use super::{did_you_mean, levenshtein};
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("StateChange", "StateChanged"), 1);
+        assert_eq!(levenshtein("AddNode", "RemoveNode"), 6);
+        assert_eq!(levenshtein("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_did_you_mean_orders_closest_first() {
+        let candidates = [
+            "StateChanged",
+            "StateChange2d",
+            "PropertyChange",
+            "BoundsChanged",
+        ];
+        assert_eq!(
+            did_you_mean("StateChange", candidates),
+            vec!["StateChanged", "StateChange2d"]
+        );
+        assert_eq!(did_you_mean("statechanged", candidates)[0], "StateChanged");
+        assert!(did_you_mean("Completely", candidates).is_empty());
+    }
+}
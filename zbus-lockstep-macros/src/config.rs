@@ -0,0 +1,35 @@
+//! The crate-wide configuration, from `lockstep.toml` or `[package.metadata.lockstep]`.
+//!
+//! The configuration is parsed by `zbus-lockstep-common`, which `zbus_lockstep::LockstepConfig`
+//! uses to read the same configuration at run time.
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+pub(crate) use zbus_lockstep_common::config::Config;
+
+use crate::Result;
+
+/// The configuration of the crate being compiled, or the empty one if it has none.
+///
+/// It is read anew on every expansion, as a proc-macro server may expand several crates in one
+/// process. Errors point at the call site, as the configuration is not part of the attribute.
+pub(crate) fn load() -> Result<Config> {
+    let Ok(manifest_dir) = std::env::var("CARGO_MANIFEST_DIR") else {
+        return Ok(Config::default());
+    };
+
+    Config::load(manifest_dir)
+        .map(Option::unwrap_or_default)
+        .map_err(|e| syn::Error::new(Span::call_site(), e.to_string()))
+}
+
+/// An item that includes the configuration file, so that the crate is rebuilt when it changes.
+pub(crate) fn tracking_item(config: &Config) -> TokenStream {
+    match &config.source {
+        Some(source) => {
+            let source = source.to_string_lossy();
+            quote! { const _: &[u8] = include_bytes!(#source); }
+        }
+        None => TokenStream::new(),
+    }
+}
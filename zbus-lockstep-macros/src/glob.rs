@@ -0,0 +1,16 @@
+//! Glob patterns for selecting several members at once.
+
+use zbus_lockstep_common::discovery::glob_match;
+
+/// Return whether a member matches a member pattern.
+///
+/// The pattern is `<interface>:<member>`, with a glob for each, or `<member>` alone for a member
+/// of any interface.
+pub(crate) fn member_match(pattern: &str, interface: &str, member: &str) -> bool {
+    match pattern.rsplit_once(':') {
+        Some((interface_pattern, member_pattern)) => {
+            glob_match(interface_pattern, interface) && glob_match(member_pattern, member)
+        }
+        None => glob_match(pattern, member),
+    }
+}
@@ -0,0 +1,706 @@
+//! Validation of zbus `#[interface]` impl blocks and `#[proxy]` traits.
+
+use std::collections::BTreeMap;
+
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{
+    Attribute, FnArg, Ident, ImplItem, ItemImpl, LitStr, Meta, ReturnType, Signature, Token,
+    TraitItemFn, Type, parse::ParseStream, punctuated::Punctuated, visit_mut::VisitMut,
+};
+
+use zbus_lockstep_common::suggest::{did_you_mean, format_suggestions};
+
+use zbus_lockstep_common::discovery::XmlFilter;
+
+use crate::{
+    Result, XmlFile,
+    config::{self, Config},
+    read_xml_files,
+};
+
+/// The arguments of `#[validate_interface]`.
+pub(crate) struct InterfaceArgs {
+    // Optional paths to XML directories, searched in order
+    pub(crate) xml: Vec<LitStr>,
+
+    // Optional interface name, overriding the name given to `#[interface]`
+    pub(crate) interface: Option<LitStr>,
+}
+
+impl syn::parse::Parse for InterfaceArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut xml = Vec::new();
+        let mut interface = None;
+
+        while !input.is_empty() {
+            let ident = input.parse::<Ident>()?;
+            match ident.to_string().as_str() {
+                "xml" => {
+                    input.parse::<Token![:]>()?;
+                    xml.push(input.parse::<LitStr>()?);
+                }
+                "interface" => {
+                    input.parse::<Token![:]>()?;
+                    interface = Some(input.parse::<LitStr>()?);
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        format!("Unexpected argument: {ident}"),
+                    ));
+                }
+            }
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        Ok(InterfaceArgs { xml, interface })
+    }
+}
+
+/// The side of the bus the validated code is on.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Side {
+    /// A `#[interface]` impl block, serving the interface.
+    Interface,
+    /// A `#[proxy]` trait, calling the interface.
+    Proxy,
+}
+
+impl Side {
+    /// The attributes naming the interface, and their argument that holds the name.
+    fn interface_attribute(self) -> (&'static [&'static str], &'static str) {
+        match self {
+            Side::Interface => (&["interface", "dbus_interface"], "name"),
+            Side::Proxy => (&["proxy", "dbus_proxy"], "interface"),
+        }
+    }
+}
+
+/// A method, signal or property as zbus exposes it.
+pub(crate) enum Member {
+    Method {
+        name: String,
+        inputs: Vec<(String, Type)>,
+        outputs: Vec<Type>,
+    },
+    Signal {
+        name: String,
+        inputs: Vec<(String, Type)>,
+    },
+    Property {
+        name: String,
+        getter: Option<Box<Type>>,
+        setter: Option<Box<Type>>,
+    },
+}
+
+/// The types of a property's getter and setter.
+type Accessors = (Option<Box<Type>>, Option<Box<Type>>);
+
+/// The parts of a `#[zbus(...)]` attribute on a method that decide how it is exposed.
+#[derive(Default)]
+struct MemberAttrs {
+    name: Option<String>,
+    signal: bool,
+    property: bool,
+    object: bool,
+}
+
+/// Expand `#[validate_interface]` on an impl block.
+pub(crate) fn expand(args: InterfaceArgs, item: ItemImpl) -> Result<TokenStream> {
+    let self_name = match item.self_ty.as_ref() {
+        Type::Path(path) => path.path.segments.last().map(|segment| &segment.ident),
+        _ => None,
+    }
+    .ok_or_else(|| syn::Error::new_spanned(&item.self_ty, "Expected a named type"))?
+    .clone();
+
+    let side = Side::Interface;
+    let interface_lit = match args.interface {
+        Some(interface_lit) => interface_lit,
+        None => interface_name_of(&item.attrs, side)?,
+    };
+    let config = config::load()?;
+    let xml_file = find_interface_file(&args.xml, &interface_lit, &config)?;
+    let interface_name = interface_lit.value();
+
+    // Signals are declared without a body, which `syn` leaves as verbatim tokens.
+    let declarations: Vec<TraitItemFn> = item
+        .items
+        .iter()
+        .filter_map(|impl_item| match impl_item {
+            ImplItem::Verbatim(tokens) => syn::parse2(tokens.clone()).ok(),
+            _ => None,
+        })
+        .collect();
+    let methods = item
+        .items
+        .iter()
+        .filter_map(|impl_item| match impl_item {
+            ImplItem::Fn(method) => Some((method.attrs.as_slice(), &method.sig)),
+            _ => None,
+        })
+        .chain(
+            declarations
+                .iter()
+                .map(|method| (method.attrs.as_slice(), &method.sig)),
+        );
+    let members = members_of(methods, side)?;
+
+    let tests = members
+        .iter()
+        .map(|member| member_test(member, &self_name, &interface_name, &xml_file, side));
+    let completeness_test = completeness_test(&members, &self_name, &interface_name, &xml_file);
+    let config_tracking = config::tracking_item(&config);
+
+    Ok(quote! {
+        #item
+
+        #config_tracking
+
+        #(#tests)*
+
+        #completeness_test
+    })
+}
+
+/// The members zbus exposes for `methods`, given as their attributes and signature.
+///
+/// A property's getter and setter are combined into a single member.
+pub(crate) fn members_of<'a>(
+    methods: impl Iterator<Item = (&'a [Attribute], &'a Signature)>,
+    side: Side,
+) -> Result<Vec<Member>> {
+    let mut members = Vec::new();
+    let mut properties: BTreeMap<String, Accessors> = BTreeMap::new();
+    for (attrs, sig) in methods {
+        match member_of(attrs, sig, side)? {
+            Member::Property {
+                name,
+                getter,
+                setter,
+            } => {
+                let property = properties.entry(name).or_default();
+                property.0 = getter.or(property.0.take());
+                property.1 = setter.or(property.1.take());
+            }
+            member => members.push(member),
+        }
+    }
+    members.extend(
+        properties
+            .into_iter()
+            .map(|(name, (getter, setter))| Member::Property {
+                name,
+                getter,
+                setter,
+            }),
+    );
+
+    Ok(members)
+}
+
+/// The interface name given to `#[interface(name = "...")]` or `#[proxy(interface = "...")]`.
+pub(crate) fn interface_name_of(attrs: &[Attribute], side: Side) -> Result<LitStr> {
+    let (attribute_names, key) = side.interface_attribute();
+
+    for attr in attrs {
+        let is_interface = attr
+            .path()
+            .segments
+            .last()
+            .is_some_and(|segment| attribute_names.iter().any(|name| segment.ident == name));
+        let Meta::List(list) = &attr.meta else {
+            continue;
+        };
+        if !is_interface {
+            continue;
+        }
+
+        let metas = list.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+        if let Some(name) = metas.iter().find_map(|meta| string_value_of(meta, key)) {
+            return Ok(name);
+        }
+    }
+
+    Err(syn::Error::new(
+        Span::call_site(),
+        format!(
+            "No interface name found, add `interface: \"...\"` or `#[{}({key} = \"...\")]`",
+            attribute_names[0]
+        ),
+    ))
+}
+
+/// Find the XML file that defines the interface named by `interface_lit`, searching the
+/// definitions directories in order.
+///
+/// Errors point at the literal they concern: `xml` or the interface name.
+pub(crate) fn find_interface_file(
+    xml: &[LitStr],
+    interface_lit: &LitStr,
+    config: &Config,
+) -> Result<XmlFile> {
+    let interface_name = interface_lit.value();
+    let interface_name = interface_name.as_str();
+    let xml_span = xml.first().map_or_else(Span::call_site, LitStr::span);
+    let mut available_interfaces = Vec::new();
+
+    for xml_file in read_xml_files(xml, &XmlFilter::resolve(None, None, Some(config)), config)? {
+        let node = zbus_xml::Node::try_from(xml_file.xml.as_str()).map_err(|e| {
+            syn::Error::new(
+                xml_span,
+                format!("Failed to parse XML file: {} Err: {e}", xml_file.source()),
+            )
+        })?;
+
+        for interface in node.interfaces() {
+            if interface.name().as_str() == interface_name {
+                return Ok(xml_file);
+            }
+            available_interfaces.push(interface.name().to_string());
+        }
+    }
+
+    let suggestions = did_you_mean(
+        interface_name,
+        available_interfaces.iter().map(String::as_str),
+    );
+    Err(syn::Error::new(
+        interface_lit.span(),
+        format!(
+            "Interface '{interface_name}' not found.{}",
+            format_suggestions(&suggestions)
+        ),
+    ))
+}
+
+/// The member that zbus exposes for a method with `attrs` and `sig`.
+fn member_of(attrs: &[Attribute], sig: &Signature, side: Side) -> Result<Member> {
+    let attrs = member_attrs(attrs)?;
+    let ident = sig.ident.to_string();
+    let ident = ident.strip_prefix("r#").unwrap_or(&ident);
+
+    // The arguments that are sent over the bus, without `self` and the arguments zbus provides.
+    // The first argument of a signal in an impl block is its emitter.
+    let skip = usize::from(attrs.signal && side == Side::Interface);
+    let inputs: Vec<(String, Type)> = sig
+        .inputs
+        .iter()
+        .filter_map(|input| match input {
+            FnArg::Typed(pat_type) => Some(pat_type),
+            FnArg::Receiver(_) => None,
+        })
+        .skip(skip)
+        .filter(|pat_type| !is_special_arg(&pat_type.attrs))
+        .map(|pat_type| {
+            let name = match pat_type.pat.as_ref() {
+                syn::Pat::Ident(pat) => pat.ident.unraw_string(),
+                pat => quote!(#pat).to_string(),
+            };
+            (name, static_lifetimes(&pat_type.ty))
+        })
+        .collect();
+
+    if attrs.signal {
+        return Ok(Member::Signal {
+            name: attrs.name.unwrap_or_else(|| pascal_case(ident)),
+            inputs,
+        });
+    }
+
+    // A proxy method returning another proxy receives the path of its object.
+    let output = match &sig.output {
+        _ if attrs.object => Some(syn::parse_quote!(zvariant::OwnedObjectPath)),
+        ReturnType::Default => None,
+        ReturnType::Type(_, ty) => Some(static_lifetimes(result_inner_type(ty))),
+    };
+
+    if attrs.property {
+        let (name, getter, setter) = match inputs.into_iter().last() {
+            Some((_, ty)) => {
+                let name = ident.strip_prefix("set_").unwrap_or(ident);
+                (name, None, Some(Box::new(ty)))
+            }
+            None => (ident, output.map(Box::new), None),
+        };
+        return Ok(Member::Property {
+            name: attrs.name.unwrap_or_else(|| pascal_case(name)),
+            getter,
+            setter,
+        });
+    }
+
+    // A tuple is returned as one out argument per element.
+    let outputs = match output {
+        None => Vec::new(),
+        Some(Type::Tuple(tuple)) => tuple.elems.into_iter().collect(),
+        Some(ty) => vec![ty],
+    };
+
+    Ok(Member::Method {
+        name: attrs.name.unwrap_or_else(|| pascal_case(ident)),
+        inputs,
+        outputs,
+    })
+}
+
+/// The parts of the `#[zbus(...)]` attributes on a method that decide how it is exposed.
+fn member_attrs(attrs: &[Attribute]) -> Result<MemberAttrs> {
+    let mut member_attrs = MemberAttrs::default();
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("zbus")) {
+        let metas = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+        for meta in metas {
+            match &meta {
+                Meta::Path(path) if path.is_ident("signal") => member_attrs.signal = true,
+                Meta::Path(path) if path.is_ident("property") => member_attrs.property = true,
+                Meta::List(list) if list.path.is_ident("property") => {
+                    member_attrs.property = true;
+                }
+                Meta::NameValue(name_value) if name_value.path.is_ident("object") => {
+                    member_attrs.object = true;
+                }
+                Meta::NameValue(_) => {
+                    member_attrs.name = string_value_of(&meta, "name")
+                        .map(|name| name.value())
+                        .or(member_attrs.name);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Ok(member_attrs)
+}
+
+/// The value of a `<key> = "..."` argument.
+fn string_value_of(meta: &Meta, key: &str) -> Option<LitStr> {
+    match meta {
+        Meta::NameValue(syn::MetaNameValue {
+            path,
+            value:
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(name),
+                    ..
+                }),
+            ..
+        }) if path.is_ident(key) => Some(name.clone()),
+        _ => None,
+    }
+}
+
+/// Whether an argument is provided by zbus rather than sent over the bus, such as the message
+/// header.
+fn is_special_arg(attrs: &[Attribute]) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("zbus"))
+        .filter_map(|attr| {
+            attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+                .ok()
+        })
+        .flatten()
+        .any(|meta| {
+            [
+                "object_server",
+                "connection",
+                "header",
+                "signal_context",
+                "signal_emitter",
+            ]
+            .iter()
+            .any(|special| meta.path().is_ident(special))
+        })
+}
+
+/// The `T` of a `Result<T>`, or the type itself.
+fn result_inner_type(ty: &Type) -> &Type {
+    let Type::Path(path) = ty else {
+        return ty;
+    };
+    let Some(segment) = path.path.segments.last() else {
+        return ty;
+    };
+    if segment.ident != "Result" {
+        return ty;
+    }
+
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => args
+            .args
+            .iter()
+            .find_map(|arg| match arg {
+                syn::GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .unwrap_or(ty),
+        _ => ty,
+    }
+}
+
+/// `ty` with its lifetimes replaced by `'static`, so that it can be named in a test function.
+pub(crate) fn static_lifetimes(ty: &Type) -> Type {
+    struct StaticLifetimes;
+
+    impl VisitMut for StaticLifetimes {
+        fn visit_lifetime_mut(&mut self, lifetime: &mut syn::Lifetime) {
+            *lifetime = syn::Lifetime::new("'static", lifetime.span());
+        }
+
+        fn visit_type_reference_mut(&mut self, reference: &mut syn::TypeReference) {
+            reference.lifetime = Some(syn::Lifetime::new("'static", Span::call_site()));
+            syn::visit_mut::visit_type_reference_mut(self, reference);
+        }
+    }
+
+    let mut ty = ty.clone();
+    StaticLifetimes.visit_type_mut(&mut ty);
+    ty
+}
+
+/// Convert a `snake_case` name to `PascalCase`, as zbus does for member names.
+pub(crate) fn pascal_case(name: &str) -> String {
+    name.split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// A test comparing a member's signatures with the XML.
+pub(crate) fn member_test(
+    member: &Member,
+    self_name: &Ident,
+    interface_name: &str,
+    xml_file: &XmlFile,
+    side: Side,
+) -> TokenStream {
+    let (name, kind, checks) = match member {
+        Member::Method {
+            name,
+            inputs,
+            outputs,
+        } => {
+            let arg_names = inputs.iter().map(|(name, _)| name);
+            let arg_types = inputs.iter().map(|(_, ty)| ty);
+            let checks = quote! {
+                let xml_signature = zbus_lockstep::get_method_args_type_with_shape(
+                    xml.as_bytes(),
+                    #interface_name,
+                    #name,
+                    zbus_lockstep::ArgSelector::All,
+                    zbus_lockstep::BodyShape::Struct,
+                ).unwrap_or_else(|e| panic!("{e}"));
+                zbus_lockstep::assert_signature_eq!(
+                    xml_signature,
+                    <(#(#arg_types,)*) as Type>::SIGNATURE,
+                    &[#(#arg_names),*]
+                );
+
+                let xml_signature = zbus_lockstep::get_method_return_type_with_shape(
+                    xml.as_bytes(),
+                    #interface_name,
+                    #name,
+                    zbus_lockstep::ArgSelector::All,
+                    zbus_lockstep::BodyShape::Struct,
+                ).unwrap_or_else(|e| panic!("{e}"));
+                zbus_lockstep::assert_signature_eq!(
+                    xml_signature,
+                    <(#(#outputs,)*) as Type>::SIGNATURE,
+                    &[]
+                );
+            };
+            (name, "method", checks)
+        }
+        Member::Signal { name, inputs } => {
+            let arg_names = inputs.iter().map(|(name, _)| name);
+            let arg_types = inputs.iter().map(|(_, ty)| ty);
+            let checks = quote! {
+                let xml_signature = zbus_lockstep::get_signal_body_type_with_shape(
+                    xml.as_bytes(),
+                    #interface_name,
+                    #name,
+                    zbus_lockstep::ArgSelector::All,
+                    zbus_lockstep::BodyShape::Struct,
+                ).unwrap_or_else(|e| panic!("{e}"));
+                zbus_lockstep::assert_signature_eq!(
+                    xml_signature,
+                    <(#(#arg_types,)*) as Type>::SIGNATURE,
+                    &[#(#arg_names),*]
+                );
+            };
+            (name, "signal", checks)
+        }
+        Member::Property {
+            name,
+            getter,
+            setter,
+        } => {
+            let access = match (getter, setter) {
+                (Some(_), Some(_)) => quote! { zbus_lockstep::PropertyAccess::ReadWrite },
+                (None, Some(_)) => quote! { zbus_lockstep::PropertyAccess::Write },
+                _ => quote! { zbus_lockstep::PropertyAccess::Read },
+            };
+            // An impl block serves exactly the access of the XML, whereas a proxy may use less.
+            let access_check = match side {
+                Side::Interface => quote! { property.expect_access(#access) },
+                Side::Proxy => {
+                    let (reads, writes) = (getter.is_some(), setter.is_some());
+                    quote! {
+                        if (#reads && !property.access.read()) || (#writes && !property.access.write()) {
+                            Err(zbus_lockstep::LockstepError::PropertyAccessMismatch {
+                                name: property.name.clone(),
+                                expected: #access,
+                                actual: property.access,
+                            })
+                        } else {
+                            Ok(())
+                        }
+                    }
+                }
+            };
+            let types = getter.iter().chain(setter);
+            let checks = quote! {
+                let property = zbus_lockstep::get_property(
+                    xml.as_bytes(),
+                    #interface_name,
+                    #name,
+                ).unwrap_or_else(|e| panic!("{e}"));
+                if let Err(e) = #access_check {
+                    panic!("{e}");
+                }
+                #(
+                    zbus_lockstep::assert_signature_eq!(
+                        property.signature,
+                        <#types as Type>::SIGNATURE
+                    );
+                )*
+            };
+            (name, "property", checks)
+        }
+    };
+
+    let test_name = format_ident!("test_{self_name}_{name}_{kind}");
+    let (xml_file_path, source) = path_and_source(xml_file);
+
+    quote! {
+        #[cfg(test)]
+        #[test]
+        fn #test_name() {
+            use zvariant::Type;
+
+            // Shown should the test fail.
+            println!("{} is defined in {}", #interface_name, #source);
+            let xml = std::fs::read_to_string(#xml_file_path)
+                .expect("\"#xml_file_path\" expected to be a valid file path.");
+
+            #checks
+        }
+    }
+}
+
+/// A test reporting the members of the XML interface that the impl block does not provide.
+fn completeness_test(
+    members: &[Member],
+    self_name: &Ident,
+    interface_name: &str,
+    xml_file: &XmlFile,
+) -> TokenStream {
+    let names_of = |kind: &str| {
+        members
+            .iter()
+            .filter_map(|member| match (member, kind) {
+                (Member::Method { name, .. }, "method")
+                | (Member::Signal { name, .. }, "signal")
+                | (Member::Property { name, .. }, "property") => Some(name.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+    let methods = names_of("method");
+    let signals = names_of("signal");
+    let properties = names_of("property");
+
+    let test_name = format_ident!("test_{self_name}_interface_members");
+    let (xml_file_path, source) = path_and_source(xml_file);
+
+    quote! {
+        #[cfg(test)]
+        #[test]
+        fn #test_name() {
+            // Shown should the test fail.
+            println!("{} is defined in {}", #interface_name, #source);
+            let xml = std::fs::read_to_string(#xml_file_path)
+                .expect("\"#xml_file_path\" expected to be a valid file path.");
+            let node = zbus_lockstep::zbus_xml::Node::from_reader(xml.as_bytes())
+                .expect("Failed to parse XML file.");
+            let interface = node
+                .interfaces()
+                .iter()
+                .find(|interface| interface.name().as_str() == #interface_name)
+                .expect("Interface should be defined in XML file.");
+
+            let methods: &[&str] = &[#(#methods),*];
+            let signals: &[&str] = &[#(#signals),*];
+            let properties: &[&str] = &[#(#properties),*];
+
+            let mut missing = Vec::new();
+            for method in interface.methods() {
+                if !methods.contains(&method.name().as_str()) {
+                    missing.push(format!("method `{}`", method.name()));
+                }
+            }
+            for signal in interface.signals() {
+                if !signals.contains(&signal.name().as_str()) {
+                    missing.push(format!("signal `{}`", signal.name()));
+                }
+            }
+            for property in interface.properties() {
+                if !properties.contains(&property.name().as_str()) {
+                    missing.push(format!("property `{}`", property.name()));
+                }
+            }
+
+            assert!(
+                missing.is_empty(),
+                "`{}` does not implement these members of `{}`:{}",
+                stringify!(#self_name),
+                #interface_name,
+                missing.iter().map(|m| format!("\n  {m}")).collect::<String>()
+            );
+        }
+    }
+}
+
+/// The unraw name of an identifier, `type` for `r#type`.
+trait UnrawString {
+    fn unraw_string(&self) -> String;
+}
+
+impl UnrawString for Ident {
+    fn unraw_string(&self) -> String {
+        use syn::ext::IdentExt;
+
+        self.unraw().to_string()
+    }
+}
+
+/// The path of `xml_file`, for the generated tests to read, and where it came from.
+fn path_and_source(xml_file: &XmlFile) -> (&str, String) {
+    let path = xml_file
+        .path
+        .to_str()
+        .expect("XML file path should be valid UTF-8");
+
+    (path, xml_file.source())
+}
@@ -1,20 +1,42 @@
 //! # zbus-lockstep-macros
 //!
-//! This provides the `validate` macro that builds on `zbus-lockstep`.
+//! This provides the `validate`, `validate_interface` and `validate_proxy` macros that build on
+//! `zbus-lockstep`.
 #![doc(html_root_url = "https://docs.rs/zbus-lockstep-macros/0.6.0")]
 
+mod config;
+mod glob;
+mod interface;
+mod naming;
+mod proxy;
+
 type Result<T> = std::result::Result<T, syn::Error>;
 
-use std::{collections::HashMap, path::PathBuf};
+use std::path::PathBuf;
 
+use config::Config;
+use glob::member_match;
+use naming::Naming;
 use proc_macro::TokenStream;
-use quote::quote;
-use syn::{DeriveInput, Ident, LitStr, Token, parse::ParseStream, parse_macro_input};
+use quote::{quote, quote_spanned};
+use syn::{
+    Ident, LitStr, Token, ext::IdentExt, parse::ParseStream, parse_macro_input, spanned::Spanned,
+};
+use zbus_lockstep_common::{
+    config::{Policy, Shape},
+    discovery::{XmlFilter, discover_xml_files},
+    roots::{RootError, RootOrigin, resolve_xml_roots},
+    suggest::{did_you_mean, format_suggestions},
+};
 
-/// Validate a struct's type signature against XML signal body type.
+/// Validate a struct's type signature against XML signal body type, method arguments or property
+/// type.
+///
+/// Retrieves the signal body type, the signature of a method's arguments or reply, or a property's
+/// type from a (collection of) XML file(s) and compares it to the struct's type signature.
 ///
-/// Retrieves the signal body type from a (collection of) XML file(s) and compares it to the
-/// struct's type signature.
+/// Should the signatures differ, the test reports each divergence with its path, naming the
+/// struct's fields, e.g. ``field 4 (`child_count`): expected `i`, found `u` ``.
 ///
 /// If the XML file(s) are found in the default location, `xml/` or `XML/` of the crate root,
 /// or provided as environment variable, `LOCKSTEP_XML_PATH`, the macro can be used without
@@ -23,14 +45,54 @@ use syn::{DeriveInput, Ident, LitStr, Token, parse::ParseStream, parse_macro_inp
 ///
 /// # Arguments
 ///
-/// `#[validate]` can take three optional arguments:
+/// `#[validate]` can take eighteen optional arguments:
 ///
-/// * `xml`: Path to XML file(s) containing the signal definition.
+/// * `xml`: Path to the XML directory containing the signal definition, may be repeated.
 /// * `interface`: Interface name of the signal.
 /// * `signal`: Signal name.
+/// * `signals`: Patterns matching several signals, instead of a signal name.
+/// * `method`: Method name, instead of a signal name.
+/// * `direction`: Whether a method's arguments, `in`, or its reply, `out`, are compared.
+/// * `property`: Property name, instead of a signal name.
+/// * `access`: The access a property must have, `read`, `write` or `readwrite`.
+/// * `argument`: Selection of the signal's arguments.
+/// * `shape`: Whether the arguments are compared as a struct, `flattened`, `struct` or `auto`.
+/// * `strict`: Whether the struct's field names must follow the argument names, `true` or `false`.
+/// * `policy`: A `zbus_lockstep::SignaturePolicy` for differences to accept.
+/// * `test_name`: The name of the generated test function.
+/// * `as`: Instantiations of a generic type, such as `"Event<String>"`.
+/// * `naming`: How the member name is inferred from the type's name.
+/// * `suffixes`: The suffixes some `naming` strategies strip from the type's name.
+/// * `include`: Patterns of the XML files to read.
+/// * `exclude`: Patterns of the XML files to skip.
 ///
 /// `#[validate(xml: <xml_path>, interface: <interface_name>, member: <member_name>)]`
 ///
+/// ## Configuration
+///
+/// Defaults for all attributes of a crate are read from `lockstep.toml` next to `Cargo.toml`, or
+/// else from the `[package.metadata.lockstep]` table of `Cargo.toml`:
+///
+/// ```toml
+/// [package.metadata.lockstep]
+/// xml = ["xml", "vendor/portal"]
+/// interface = "org.example.Node"
+/// naming = "strip_suffix"
+/// suffixes = ["Event", "Signal"]
+/// shape = "auto"
+/// policy = ["allow_sign_difference", "allow_variant_widening"]
+/// include = "org/**"
+/// exclude = "**/draft/**"
+/// ```
+///
+/// `xml` lists directories relative to the crate root. `policy` names `SignaturePolicy` variants,
+/// several are combined with `SignaturePolicy::Any`. In `lockstep.toml`, the keys are at the top
+/// level. The expansion includes the configuration file, so that editing it rebuilds the crate.
+///
+/// The `LOCKSTEP_*` environment variables take precedence over the arguments, which take
+/// precedence over the configuration, for every setting. The default locations below are only
+/// searched if none of them gives the XML directories.
+///
 /// ## `xml_path`
 ///
 /// Without an argument, the macro looks for XML file(s) in `xml/` or `XML/` of the crate root.
@@ -54,6 +116,45 @@ use syn::{DeriveInput, Ident, LitStr, Token, parse::ParseStream, parse_macro_inp
 /// Alternatively, you can provide the XML directory path as environment variable,
 /// `LOCKSTEP_XML_PATH`, which will override both default and the path argument.
 ///
+/// Definitions from several sources are searched by repeating `xml:`, or by separating the
+/// directories like `PATH`, with `:` or `;` on Windows, in the argument or the environment
+/// variable. The directories are searched in order: an interface defined in more than one is
+/// taken from the first. A failing test names the file and directory the definition came from.
+///
+/// ```rust
+/// use zbus_lockstep_macros::validate;
+/// use zvariant::Type;
+///
+/// #[validate(xml: "xml/nested/draft", xml: "xml", interface: "org.example.NestedDraft")]
+/// #[derive(Type)]
+/// struct MovedSignal(String);
+/// ```
+///
+/// ## `include` and `exclude`
+///
+/// The XML files are looked up in the directory and its subdirectories, in path order. Files
+/// without the `xml` extension are skipped.
+///
+/// `include:` and `exclude:` narrow down the files that are read, each taking a pattern or a list
+/// of them. Patterns are matched against a file's path relative to the directory: `*` and `?`
+/// match within a path segment and `**` matches any number of segments. A pattern without a `/`
+/// is matched against the file name.
+///
+/// The environment variables `LOCKSTEP_XML_INCLUDE` and `LOCKSTEP_XML_EXCLUDE`, comma separated
+/// lists, set the patterns for all attributes of a crate, overriding their arguments.
+///
+/// ```rust
+/// use zbus_lockstep_macros::validate;
+/// use zvariant::Type;
+///
+/// #[validate(exclude: "**/draft/**")]
+/// #[derive(Type)]
+/// struct MovedSignal {
+///     x: i32,
+///     y: i32,
+/// }
+/// ```
+///
 /// ## `interface`
 ///
 /// If more than one signal with the same name is defined in the XML file(s),
@@ -88,6 +189,268 @@ use syn::{DeriveInput, Ident, LitStr, Token, parse::ParseStream, parse_macro_inp
 /// }
 /// ```
 ///
+/// ## `naming` and `suffixes`
+///
+/// Without a member name, the member is inferred from the type's name. `naming:` selects how:
+///
+/// * `contains`: the type's name contains the member's name, `AddNodeEvent` matches `AddNode`.
+///   This is the default.
+/// * `exact`: the type's name is the member's name.
+/// * `strip_suffix`: the type's name without a suffix is the member's name.
+/// * `longest`: of the members whose names the type's name contains, the longest.
+///   `TextChangedEvent` matches `TextChanged` rather than `Changed`.
+/// * `snake_case`: the type's name without a suffix is the member's `snake_case` name in
+///   `CamelCase`, `ObjectAddedEvent` matches `object_added`.
+///
+/// The suffixes are `Event`, `Signal`, `Reply` and `Request`, unless `suffixes:` lists others.
+///
+/// The environment variables `LOCKSTEP_NAMING` and `LOCKSTEP_SUFFIXES`, a comma separated list,
+/// set the strategy for all attributes of a crate, overriding their arguments, for instance in
+/// `.cargo/config.toml`:
+///
+/// ```toml
+/// [env]
+/// LOCKSTEP_NAMING = "strip_suffix"
+/// LOCKSTEP_SUFFIXES = "Event,Signal"
+/// ```
+///
+/// ```rust
+/// use zbus_lockstep_macros::validate;
+/// use zvariant::Type;
+///
+/// #[validate(naming: longest)]
+/// #[derive(Type)]
+/// struct TextChangedEvent {
+///     text: String,
+///     offset: i32,
+/// }
+///
+/// #[validate(naming: strip_suffix, suffixes: ["Notification"])]
+/// #[derive(Type)]
+/// struct ChangedNotification(String);
+/// ```
+///
+/// ## `signals`
+///
+/// A type can be the body of many signals. `signals:` takes a pattern, or a list of patterns, and
+/// validates the type against each signal that matches. A pattern is `<interface>:<member>`, or
+/// `<member>` for a signal of any interface, where `*` matches any run of characters and `?` a
+/// single character. The test reports each signal whose arguments do not match.
+///
+/// ```rust
+/// use zbus_lockstep_macros::validate;
+/// use zvariant::{OwnedObjectPath, Type};
+///
+/// #[validate(signals: "org.example.Node:*Node")]
+/// #[derive(Type)]
+/// struct NodeEvent {
+///    name: String,
+///    path: OwnedObjectPath,
+/// }
+///
+/// #[validate(signals: ["AddNode", "RemoveNode"])]
+/// #[derive(Type)]
+/// struct NodeChange {
+///    name: String,
+///    path: OwnedObjectPath,
+/// }
+/// ```
+///
+/// ## `method` and `direction`
+///
+/// Types sent to or returned by a method are validated with `method:`. `direction: in` compares
+/// the type to the arguments of the call, `direction: out` to the arguments of the reply.
+///
+/// Without `signal:` or `method:`, a type whose name ends in `Reply` is compared to the reply of
+/// the method its name contains, and a type whose name ends in `Request` to the method's
+/// arguments, if no signal or property matches the name. With `direction:`, only methods are
+/// searched.
+///
+/// ```rust
+/// use zbus_lockstep_macros::validate;
+/// use zvariant::Type;
+///
+/// #[validate(method: "RequestName", direction: in)]
+/// #[derive(Type)]
+/// struct NameRequest {
+///    apple: String,
+///    orange: u32,
+/// }
+///
+/// // The reply of `RequestName`.
+/// #[validate]
+/// #[derive(Type)]
+/// struct RequestNameReply(u32);
+/// ```
+///
+/// ## `property` and `access`
+///
+/// A property's type is validated with `property:`. Without `signal:`, `method:` or `property:`,
+/// a type whose name contains no signal name is compared to the property its name contains, so
+/// that a newtype `Features` is compared to the `Features` property.
+///
+/// `access:` also requires the property to be `read`, `write` or `readwrite`. `argument:`,
+/// `shape:` and `strict:` do not apply to properties.
+///
+/// ```rust
+/// use zbus_lockstep_macros::validate;
+/// use zvariant::Type;
+///
+/// #[validate]
+/// #[derive(Type)]
+/// struct Features(Vec<String>);
+///
+/// #[validate(property: "Features", access: read)]
+/// type FeatureList = Vec<String>;
+/// ```
+///
+/// ## `argument`
+///
+/// By default the type is compared to all of the signal's arguments. `argument:` selects a part
+/// of them instead: an argument name, a position, a range of positions, such as `1..3` or `1..`,
+/// or a list of names. Positions count from zero and also select arguments that have no name.
+///
+/// ```rust
+/// use zbus_lockstep_macros::validate;
+/// use zvariant::Type;
+///
+/// #[validate(signal: "Alert", argument: 1..3)]
+/// #[derive(Type)]
+/// struct AlertSound {
+///    color: String,
+///    volume: f64,
+/// }
+///
+/// #[validate(signal: "Alert", argument: "urgent")]
+/// #[derive(Type)]
+/// struct Urgency(bool);
+/// ```
+///
+/// ## `shape`
+///
+/// A `#[derive(Type)]` struct has a structure signature, even with a single field: `(o)` rather
+/// than `o`. A newtype or a plain value has the signature of the value itself. By default, with
+/// `shape: auto`, either is accepted. Use `shape: struct` or `shape: flattened` to insist on one.
+///
+/// ```rust
+/// use zbus_lockstep_macros::validate;
+/// use zvariant::Type;
+///
+/// #[validate(signal: "U32AsEnum", shape: struct)]
+/// #[derive(Type)]
+/// struct U32AsEnumSignal {
+///    my_type: u32,
+/// }
+/// ```
+///
+/// ## Signals without arguments
+///
+/// A signal without arguments has the unit signature, as have `()` and unit structs. `validate`
+/// accepts both a unit struct and a type alias:
+///
+/// ```rust
+/// use zbus_lockstep_macros::validate;
+/// use zvariant::Type;
+///
+/// #[validate(signal: "Reset")]
+/// #[derive(Type)]
+/// struct ResetSignal;
+///
+/// #[validate(signal: "Reset")]
+/// type ResetBody = ();
+/// ```
+///
+/// ## `strict`
+///
+/// Signatures do not tell two fields of the same type apart, so swapping `name` and `path`, both
+/// strings, goes unnoticed. With `strict: true`, the test also compares the struct's field names
+/// to the argument names, in order. Argument names are converted to snake case first, so
+/// `nodeAdded` matches `node_added`, and leading underscores of field names are ignored. Renamed,
+/// reordered, missing and unexpected fields fail the test.
+///
+/// Strict mode requires a struct with named fields. If a single argument is compared to a struct
+/// with several fields, those fields are the argument's own structure, which has no names in XML,
+/// so the names are not compared.
+///
+/// ```rust
+/// use zbus_lockstep_macros::validate;
+/// use zvariant::Type;
+///
+/// #[validate(signal: "Alert", strict: true)]
+/// #[derive(Type)]
+/// struct AlertSignal {
+///    urgent: bool,
+///    color: String,
+///    volume: f64,
+/// }
+/// ```
+///
+/// ## `policy`
+///
+/// Some types deliberately differ from the XML, such as a `#[repr(u32)]` enum for an `i`
+/// argument. `policy:` takes an expression of type `zbus_lockstep::SignaturePolicy` that accepts
+/// such differences. The test prints each difference it accepted, and the relaxation that
+/// accepted it.
+///
+/// ```rust
+/// use zbus_lockstep::SignaturePolicy;
+/// use zbus_lockstep_macros::validate;
+/// use zvariant::Type;
+///
+/// #[validate(signal: "Level", policy: SignaturePolicy::AllowSignDifference)]
+/// #[derive(Type)]
+/// #[repr(u32)]
+/// enum Level {
+///     Low,
+///     High,
+/// }
+/// ```
+///
+/// ## `test_name` and multiple attributes
+///
+/// The generated test is named `test_<Type>_type_signature`. `test_name:` names it otherwise.
+///
+/// `validate` can be applied more than once to the same type, for instance when the type is both
+/// a signal body and a method's reply. The first attribute's test keeps the default name, the
+/// tests of the following attributes are numbered: `test_<Type>_type_signature_1` and so on.
+/// Stacked attributes are recognized as `validate`, `zbus_lockstep::validate` or
+/// `zbus_lockstep_macros::validate`.
+///
+/// ```rust
+/// use zbus_lockstep_macros::validate;
+/// use zvariant::Type;
+///
+/// #[validate(signal: "U32AsEnum")]
+/// #[validate(method: "RequestName", direction: out, test_name: "test_grape_signature")]
+/// #[derive(Type)]
+/// struct Grape(u32);
+/// ```
+///
+/// ## `as`
+///
+/// A generic type has no signature of its own. `as:` takes the instantiation to validate, or a
+/// list of them, each of which must match the XML. Lifetimes, whether of a type without other
+/// parameters or in the instantiations, are made `'static`.
+///
+/// ```rust
+/// use zvariant::{OwnedObjectPath, Type};
+/// use zbus_lockstep_macros::validate;
+///
+/// #[validate(signal: "RemoveNode", as: ["Event<String>", "Event<&str>"])]
+/// #[derive(Type)]
+/// struct Event<T: Type> {
+///     name: T,
+///     path: OwnedObjectPath,
+/// }
+///
+/// #[validate(signal: "Rename")]
+/// #[derive(Type)]
+/// struct Rename<'a> {
+///     old_name: &'a str,
+///     new_name: &'a str,
+/// }
+/// ```
+///
 /// ## Multiple arguments
 ///
 /// You can provide multiple arguments with a comma separated list.
@@ -111,71 +474,98 @@ pub fn validate(args: TokenStream, input: TokenStream) -> TokenStream {
     // Parse the macro arguments.
     let args = parse_macro_input!(args as ValidateArgs);
 
-    // Parse the item struct.
-    let item = parse_macro_input!(input as DeriveInput);
-    let item_name = item.ident.to_string();
+    // Parse the item: a struct, enum or union deriving `Type`, or a type alias such as
+    // `type Reset = ();`.
+    let mut item = parse_macro_input!(input as syn::Item);
 
-    let xml_str = args.xml.as_ref().and_then(|p| p.to_str());
+    // Further `validate` attributes on the item expand after this one. The first tells them
+    // their position, so that each names its test uniquely.
+    let position = args.position.unwrap_or(0);
+    if args.position.is_none() {
+        number_stacked_attributes(&mut item);
+    }
 
-    let xml = match resolve_xml_path(xml_str) {
-        Ok(xml) => xml,
-        Err(e) => {
-            return syn::Error::new(
-                proc_macro2::Span::call_site(),
-                format!("Failed to resolve XML path: {e}"),
+    let item_ident = match &item {
+        syn::Item::Struct(item) => item.ident.clone(),
+        syn::Item::Enum(item) => item.ident.clone(),
+        syn::Item::Union(item) => item.ident.clone(),
+        syn::Item::Type(item) => item.ident.clone(),
+        item => {
+            return syn::Error::new_spanned(
+                item,
+                "`validate` expects a struct, enum, union or type alias",
             )
             .to_compile_error()
             .into();
         }
     };
+    let item_name = item_ident.to_string();
+
+    // The types whose signatures are validated: the item itself, or its instantiations if it is
+    // generic.
+    let instances = match args.instances(&item, &item_ident) {
+        Ok(instances) => instances,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let (instance_names, instance_types): (Vec<String>, Vec<syn::Type>) =
+        instances.into_iter().unzip();
 
-    // Store each file's XML as a string in a with the XML's file path as key.
-    let mut xml_files: HashMap<PathBuf, String> = HashMap::new();
-    let read_dir = std::fs::read_dir(xml);
+    // The names of a struct's fields, so that a mismatch can be reported by field name.
+    let field_names: Vec<String> = match &item {
+        syn::Item::Struct(item) => item
+            .fields
+            .iter()
+            .filter_map(|field| field.ident.as_ref())
+            .map(|ident| ident.unraw().to_string())
+            .collect(),
+        _ => Vec::new(),
+    };
 
-    // If the path does not exist, the process lacks permissions to read the path,
-    // or the path is not a directory, return an error.
-    if let Err(e) = read_dir {
+    // Strict mode compares field names, which only structs with named fields have.
+    if let Some(strict) = args
+        .strict
+        .as_ref()
+        .filter(|strict| strict.value && field_names.is_empty())
+    {
         return syn::Error::new(
-            proc_macro2::Span::call_site(),
-            format!("Failed to read XML directory: {e}"),
+            strict.span(),
+            "`strict: true` requires a struct with named fields",
         )
         .to_compile_error()
         .into();
     }
 
-    // Iterate over the directory and store each XML file as a string.
-    for entry in read_dir.expect("Failed to read XML directory") {
-        let entry = entry.expect("Failed to read XML file");
-
-        // Skip directories.
-        if entry.path().is_dir() {
-            continue;
-        }
+    // Errors about the XML files point at `xml:`, if given.
+    let xml_span = args
+        .xml
+        .first()
+        .map_or_else(proc_macro2::Span::call_site, LitStr::span);
 
-        if entry.path().extension().expect("File has no extension.") == "xml" {
-            let xml =
-                std::fs::read_to_string(entry.path()).expect("Unable to read XML file to string");
-            xml_files.insert(entry.path().clone(), xml);
-        }
-    }
+    let config = match config::load() {
+        Ok(config) => config,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let filter = XmlFilter::resolve(args.include.clone(), args.exclude.clone(), Some(&config));
+    let xml_files = match read_xml_files(&args.xml, &filter, &config) {
+        Ok(xml_files) => xml_files,
+        Err(e) => return e.to_compile_error().into(),
+    };
 
-    // These are later needed to call `get_signal_body_type`.
-    let mut xml_file_path = None;
-    let mut interface_name = None;
-    let mut signal_name = None;
+    let kinds = match args.member_kinds(&item_name) {
+        Ok(kinds) => kinds,
+        Err(e) => return e.to_compile_error().into(),
+    };
 
-    // Iterate over `xml_files` and find the signal that is contained in the struct's name.
-    // Or if `signal_arg` is provided, use that.
-    for (path_key, xml_string) in xml_files {
-        let node = zbus_xml::Node::try_from(xml_string.as_str());
+    let mut nodes = Vec::new();
+    for xml_file in &xml_files {
+        let node = zbus_xml::Node::try_from(xml_file.xml.as_str());
 
         if node.is_err() {
             return syn::Error::new(
-                proc_macro2::Span::call_site(),
+                xml_span,
                 format!(
-                    "Failed to parse XML file: \"{}\" Err: {}",
-                    path_key.to_str().unwrap(),
+                    "Failed to parse XML file: {} Err: {}",
+                    xml_file.source(),
                     node.err().unwrap()
                 ),
             )
@@ -183,142 +573,1132 @@ pub fn validate(args: TokenStream, input: TokenStream) -> TokenStream {
             .into();
         }
 
-        let node = node.unwrap();
+        nodes.push((xml_file, node.unwrap()));
+    }
 
-        for interface in node.interfaces() {
-            // We were called with an interface argument, so if the interface name does not match,
-            // skip it.
-            if args.interface.is_some()
-                && interface.name().as_str() != args.interface.as_ref().unwrap()
-            {
-                continue;
-            }
+    // These are later needed to call `get_signal_body_type` or its equivalents.
+    let mut kind = kinds[0];
+    let mut xml_file = None;
+    let mut interface_name = None;
+    let mut member_name = None;
 
-            for signal in interface.signals() {
-                if args.signal.is_some() && signal.name().as_str() != args.signal.as_ref().unwrap()
-                {
+    // The members matched by `signals`, as (file, interface, member).
+    let mut targets: Vec<(&XmlFile, String, String)> = Vec::new();
+
+    // Names seen along the way, to suggest alternatives should the search fail.
+    let mut available_interfaces: Vec<String> = Vec::new();
+    let mut available_members: Vec<String> = Vec::new();
+
+    // Iterate over the documents and find the member that is contained in the struct's name.
+    // Or if `signal`, `method` or `property` is provided, use that. Kinds are searched in order,
+    // until one has a match.
+    let wanted_lit = args
+        .signal
+        .as_ref()
+        .or(args.method.as_ref())
+        .or(args.property.as_ref());
+    let wanted_member = wanted_lit.map(LitStr::value);
+    let wanted_interface = args
+        .interface
+        .as_ref()
+        .map(LitStr::value)
+        .or_else(|| config.interface.clone());
+    let patterns = args
+        .signals
+        .as_ref()
+        .map(|patterns| patterns.iter().map(LitStr::value).collect::<Vec<_>>());
+    let naming = match naming::resolve(args.naming, &config) {
+        Ok(naming) => naming,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let suffixes = naming::resolve_suffixes(args.suffixes.clone(), &config);
+    for candidate_kind in &kinds {
+        if interface_name.is_some() || !targets.is_empty() {
+            break;
+        }
+        kind = *candidate_kind;
+        let kind_name = kind.name();
+        available_interfaces.clear();
+        let mut best_score = None;
+        let mut best_matches: Vec<String> = Vec::new();
+
+        for (file, node) in &nodes {
+            for interface in node.interfaces() {
+                // An interface defined in several files is taken from the first, so that earlier
+                // definitions directories take precedence.
+                if available_interfaces.contains(&interface.name().to_string()) {
                     continue;
                 }
+                available_interfaces.push(interface.name().to_string());
 
-                let xml_signal_name = signal.name();
-
-                if args.signal.is_some()
-                    && xml_signal_name.as_str() == args.signal.as_ref().unwrap()
+                // We were called with an interface argument, so if the interface name does not
+                // match, skip it.
+                if wanted_interface
+                    .as_ref()
+                    .is_some_and(|wanted| interface.name().as_str() != wanted)
                 {
-                    interface_name = Some(interface.name().to_string());
-                    signal_name = Some(xml_signal_name.to_string());
-                    xml_file_path = Some(path_key.clone());
                     continue;
                 }
 
-                if item_name.contains(xml_signal_name.as_str()) {
-                    // If we have found a signal with the same name in an earlier iteration:
-                    if interface_name.is_some() && signal_name.is_some() {
-                        return syn::Error::new(
-                            proc_macro2::Span::call_site(),
-                            "Multiple interfaces with the same signal name. Please disambiguate.",
-                        )
-                        .to_compile_error()
-                        .into();
+                let xml_member_names: Vec<String> = match kind {
+                    MemberKind::Signal => interface
+                        .signals()
+                        .iter()
+                        .map(|signal| signal.name().to_string())
+                        .collect(),
+                    MemberKind::Method(_) => interface
+                        .methods()
+                        .iter()
+                        .map(|method| method.name().to_string())
+                        .collect(),
+                    MemberKind::Property => interface
+                        .properties()
+                        .iter()
+                        .map(|property| property.name().to_string())
+                        .collect(),
+                };
+
+                for xml_member_name in xml_member_names {
+                    available_members.push(xml_member_name.clone());
+
+                    if let Some(patterns) = &patterns {
+                        let interface_name = interface.name().to_string();
+                        let target = (*file, interface_name, xml_member_name);
+                        if patterns
+                            .iter()
+                            .any(|pattern| member_match(pattern, &target.1, &target.2))
+                            && !targets.iter().any(|t| t.1 == target.1 && t.2 == target.2)
+                        {
+                            targets.push(target);
+                        }
+                        continue;
+                    }
+
+                    if let Some(wanted) = &wanted_member {
+                        if xml_member_name == *wanted {
+                            interface_name = Some(interface.name().to_string());
+                            member_name = Some(xml_member_name);
+                            xml_file = Some(*file);
+                        }
+                        continue;
+                    }
+
+                    let Some(score) =
+                        naming::score(naming, &item_name, &suffixes, &xml_member_name)
+                    else {
+                        continue;
+                    };
+                    // Only the best matches count; of these there must be just one.
+                    let matched = format!(
+                        "{}.{xml_member_name} in {}",
+                        interface.name(),
+                        file.source()
+                    );
+                    match best_score {
+                        Some(best) if score < best => continue,
+                        Some(best) if score == best => best_matches.push(matched),
+                        _ => best_matches = vec![matched],
                     }
+                    best_score = Some(score);
                     interface_name = Some(interface.name().to_string());
-                    signal_name = Some(xml_signal_name.to_string());
-                    xml_file_path = Some(path_key.clone());
+                    member_name = Some(xml_member_name);
+                    xml_file = Some(*file);
                 }
             }
         }
+
+        if best_matches.len() > 1 {
+            return syn::Error::new(
+                item_ident.span(),
+                format!(
+                    "Multiple {kind_name}s match '{item_name}' with naming `{}`: {}. Please disambiguate.",
+                    naming.name(),
+                    best_matches.join(", ")
+                ),
+            )
+            .to_compile_error()
+            .into();
+        }
     }
+    let kind_name = kinds
+        .iter()
+        .map(|kind| kind.name())
+        .collect::<Vec<_>>()
+        .join(" or ");
 
     // Lets be nice and provide a informative compiler error message.
 
+    // We were asked to look in an interface that none of the XML files define. A configured
+    // interface is reported at the call site.
+    if let Some(iface) = wanted_interface
+        .as_ref()
+        .filter(|iface| !available_interfaces.contains(iface))
+    {
+        let (span, what) = match &args.interface {
+            Some(iface_lit) => (iface_lit.span(), "Interface"),
+            None => (proc_macro2::Span::call_site(), "Configured interface"),
+        };
+        let suggestions = did_you_mean(iface, available_interfaces.iter().map(String::as_str));
+        return syn::Error::new(
+            span,
+            format!(
+                "{what} '{iface}' not found.{}",
+                format_suggestions(&suggestions)
+            ),
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    // None of the patterns matched a signal.
+    if let Some(pattern_lits) = args.signals.as_ref().filter(|_| targets.is_empty()) {
+        let patterns = pattern_lits.iter().map(LitStr::value).collect::<Vec<_>>();
+        return syn::Error::new(
+            pattern_lits
+                .first()
+                .map_or_else(proc_macro2::Span::call_site, LitStr::span),
+            format!("No signal matching {} found.", patterns.join(", ")),
+        )
+        .to_compile_error()
+        .into();
+    }
+
     // We searched all XML files and did not find a match.
-    if interface_name.is_none() {
+    if targets.is_empty() && interface_name.is_none() {
+        // An inferred name is reported with the strategy that inferred it.
+        let inferred_by = match wanted_member {
+            Some(_) => String::new(),
+            None => format!(" with naming `{}`", naming.name()),
+        };
+        let wanted = wanted_member.unwrap_or_else(|| item_name.clone());
+        let suggestions = did_you_mean(&wanted, available_members.iter().map(String::as_str));
+        // Point at the member name if given, else at the name it was inferred from.
+        let span = wanted_lit.map_or_else(|| item_ident.span(), LitStr::span);
         return syn::Error::new(
-            proc_macro2::Span::call_site(),
+            span,
             format!(
-                "No interface matching signal name '{}' found.",
-                args.signal.unwrap_or_else(|| item_name.clone())
+                "No interface matching {kind_name} name '{wanted}' found{inferred_by}.{}",
+                format_suggestions(&suggestions)
             ),
         )
         .to_compile_error()
         .into();
     }
 
-    // If we did find a matching interface we have also set `xml_file_path` and `signal_name`.
+    // If we did find a matching interface we have also set `xml_file` and `member_name`.
 
-    let interface_name = interface_name.expect("Interface should have been found in search loop.");
-    let signal_name = signal_name.expect("Signal should have been found in search loop.");
+    if let Err(e) = args.check_applicable(kind) {
+        return e.to_compile_error().into();
+    }
+
+    if targets.is_empty() {
+        targets.push((
+            xml_file.expect("XML file should be found in search loop."),
+            interface_name.expect("Interface should have been found in search loop."),
+            member_name.expect("Member should have been found in search loop."),
+        ));
+    }
+    targets.sort_by(|a, b| (&a.0.path, &a.1, &a.2).cmp(&(&b.0.path, &b.1, &b.2)));
 
-    let xml_file_path = xml_file_path.expect("XML file path should be found in search loop.");
-    let xml_file_path = xml_file_path
-        .to_str()
-        .expect("XML file path should be valid UTF-8");
+    let xml_file_paths = targets.iter().map(|(file, _, _)| {
+        file.path
+            .to_str()
+            .expect("XML file path should be valid UTF-8")
+            .to_owned()
+    });
+    let sources = targets.iter().map(|(file, _, _)| file.source());
+    let interface_names = targets.iter().map(|(_, interface, _)| interface);
+    let member_names = targets.iter().map(|(_, _, member)| member);
 
     // Create a block to return the item struct with a uniquely named validation test.
-    let test_name = format!("test_{item_name}_type_signature");
-    let test_name = Ident::new(&test_name, proc_macro2::Span::call_site());
+    let test_name = match &args.test_name {
+        Some(test_name) => test_name.clone(),
+        None if position > 0 => Ident::new(
+            &format!("test_{item_name}_type_signature_{position}"),
+            proc_macro2::Span::call_site(),
+        ),
+        None => Ident::new(
+            &format!("test_{item_name}_type_signature"),
+            proc_macro2::Span::call_site(),
+        ),
+    };
+
+    let item_name = Ident::new(&item_ident.to_string(), proc_macro2::Span::call_site());
+
+    // A type that does not implement `Type` is reported at the item's identifier.
+    let instances = quote_spanned! {item_ident.span()=>
+        #((#instance_names, <#instance_types as Type>::SIGNATURE)),*
+    };
+
+    let argument = args.argument_tokens();
+    let shape = args.shape_tokens(&config);
+    let policy = args.policy_tokens(&config);
+
+    let strict = args.strict.as_ref().is_some_and(|strict| strict.value);
+    let strict_check = kind.arg_names_lookup().filter(|_| strict).map(
+        |(arg_names_lookup, arg_names_direction)| {
+            quote! {
+                let arg_names = #arg_names_lookup(
+                    xml.as_bytes(),
+                    interface_name,
+                    member_name,
+                    #arg_names_direction
+                    #argument,
+                ).expect("Failed to get argument names from XML file.");
+
+                // A single argument compared to several fields is a structure, whose fields have
+                // no names in XML.
+                if arg_names.len() != 1 || field_names.len() == 1 {
+                    let mismatches = zbus_lockstep::compare_arg_names(&arg_names, field_names);
+                    if !mismatches.is_empty() {
+                        failures.push(format!(
+                            "Fields of `{}` do not follow the arguments of `{member_name}`:{}",
+                            stringify!(#item_name),
+                            mismatches.iter().map(|m| format!("\n  {m}")).collect::<String>()
+                        ));
+                    }
+                }
+            }
+        },
+    );
+
+    // The signatures of the XML to compare to.
+    let item_signatures_from_xml = match kind {
+        MemberKind::Property => {
+            let access_check = args.access_tokens().map(|access| {
+                quote! {
+                    if let Err(e) = property.expect_access(#access) {
+                        failures.push(e.to_string());
+                    }
+                }
+            });
 
-    let item_name = item.ident.clone();
-    let item_name = Ident::new(&item_name.to_string(), proc_macro2::Span::call_site());
+            quote! {
+                let property = zbus_lockstep::get_property(
+                    xml.as_bytes(),
+                    interface_name,
+                    member_name,
+                ).expect("Failed to get property from XML file.");
+                #access_check
 
+                let item_signatures_from_xml = vec![property.signature];
+            }
+        }
+        MemberKind::Signal | MemberKind::Method(_) => {
+            let signature_lookup = kind.signature_lookup();
+
+            quote! {
+                // The signature of the XML in each acceptable shape. The one that is closest to
+                // the struct's signature is compared, so that a mismatch is reported against the
+                // shape that was most likely intended.
+                let item_signatures_from_xml = #shape
+                    .candidates()
+                    .iter()
+                    .map(|shape| {
+                        #signature_lookup(
+                            xml.as_bytes(),
+                            interface_name,
+                            member_name,
+                            #argument,
+                            *shape,
+                        ).expect("Failed to get signature from XML file.")
+                    })
+                    .collect::<Vec<_>>();
+            }
+        }
+    };
+
+    let config_tracking = config::tracking_item(&config);
     let item_plus_validation_test = quote! {
         #item
 
+        #config_tracking
+
         #[cfg(test)]
         #[test]
         fn #test_name() {
             use zvariant::Type;
 
-            let xml_file = std::fs::File::open(#xml_file_path).expect("\"#xml_file_path\" expected to be a valid file path." );
-            let item_signature_from_xml = zbus_lockstep::get_signal_body_type(
-                xml_file,
-                #interface_name,
-                #signal_name,
-                None
-            ).expect("Failed to get signal body type from XML file.");
-            let item_signature_from_struct = <#item_name as Type>::SIGNATURE;
+            let instances: &[(&str, &zvariant::Signature)] = &[#instances];
+            let field_names: &[&str] = &[#(#field_names),*];
+            let policy: zbus_lockstep::SignaturePolicy = #policy;
+
+            // Each member is checked, so that all members that do not match are reported.
+            let targets: &[(&str, &str, &str, &str)] = &[
+                #((#xml_file_paths, #sources, #interface_names, #member_names)),*
+            ];
+            let mut failures: Vec<String> = Vec::new();
+            for ((instance, item_signature_from_struct), (xml_file_path, source, interface_name, member_name)) in
+                instances.iter().flat_map(|instance| targets.iter().map(move |target| (instance, target)))
+            {
+                let xml = std::fs::read_to_string(xml_file_path)
+                    .unwrap_or_else(|_| panic!("\"{xml_file_path}\" expected to be a valid file path."));
+                let failures_before = failures.len();
+
+                #item_signatures_from_xml
+                let diff = item_signatures_from_xml
+                    .iter()
+                    .map(|signature| {
+                        zbus_lockstep::SignatureDiff::with_policy(
+                            signature,
+                            item_signature_from_struct,
+                            field_names,
+                            &policy,
+                        )
+                    })
+                    .min_by_key(|diff| diff.mismatches().len())
+                    .expect("At least one shape is acceptable.");
+
+                if !diff.is_empty() {
+                    failures.push(diff.to_string());
+                }
+                for relaxation in diff.relaxations() {
+                    println!("`{instance}` accepted by policy for `{member_name}`: {relaxation}");
+                }
+
+                #strict_check
+
+                for failure in &mut failures[failures_before..] {
+                    *failure = if instances.len() > 1 {
+                        format!("{interface_name}.{member_name} as `{instance}`: {failure}\n  defined in {source}")
+                    } else {
+                        format!("{interface_name}.{member_name}: {failure}\n  defined in {source}")
+                    };
+                }
+            }
 
-            assert_eq!(&item_signature_from_xml, item_signature_from_struct);
+            assert!(failures.is_empty(), "{}", failures.join("\n\n"));
         }
     };
 
     item_plus_validation_test.into()
 }
 
+/// Validate a zbus `#[interface]` impl block against its XML interface definition.
+///
+/// Generates a test for each method, signal and property of the impl block, comparing the
+/// signatures of its arguments and return type with the XML, and checking the access of each
+/// property. A further test, `test_<Type>_interface_members`, reports the members of the XML
+/// interface that the impl block does not provide.
+///
+/// Members are named the way zbus names them: the method name in `PascalCase`, without `set_` for
+/// property setters, unless `#[zbus(name = "...")]` says otherwise. Arguments zbus provides, such
+/// as `#[zbus(header)]`, and the emitter of a signal are not part of the comparison.
+///
+/// The attribute must be placed above `#[interface]`, so that it sees the impl block as written.
+///
+/// # Arguments
+///
+/// `#[validate_interface]` can take two optional arguments:
+///
+/// * `xml`: Path to the XML directory containing the interface definition, may be repeated.
+/// * `interface`: Interface name, if `#[interface]` is not given a `name`.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus::{interface, object_server::SignalEmitter};
+/// use zbus_lockstep_macros::validate_interface;
+///
+/// struct Node;
+///
+/// #[validate_interface(xml: "xml")]
+/// #[interface(name = "org.example.Node")]
+/// impl Node {
+///     fn request_name(&self, apple: String, orange: u32) -> u32 {
+///         orange
+///     }
+///
+///     #[zbus(property)]
+///     fn features(&self) -> Vec<String> {
+///         Vec::new()
+///     }
+///
+///     #[zbus(signal)]
+///     async fn rename(
+///         emitter: &SignalEmitter<'_>,
+///         old_name: &str,
+///         new_name: &str,
+///     ) -> zbus::Result<()>;
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn validate_interface(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as interface::InterfaceArgs);
+    let item = parse_macro_input!(input as syn::ItemImpl);
+
+    interface::expand(args, item)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Validate a zbus `#[proxy]` trait against its XML interface definition.
+///
+/// Generates a test for each method, signal and property of the trait, comparing the signatures
+/// of its arguments and return type with the XML. A property must be readable in the XML if the
+/// trait has a getter for it, and writable if it has a setter.
+///
+/// Members are named the way zbus names them, see [`macro@validate_interface`]. A method marked
+/// `#[zbus(object = "...")]` is expected to return an object path.
+///
+/// The attribute must be placed above `#[proxy]`, so that it sees the trait as written.
+///
+/// # Arguments
+///
+/// `#[validate_proxy]` can take two optional arguments:
+///
+/// * `xml`: Path to the XML directory containing the interface definition, may be repeated.
+/// * `interface`: Interface name, if `#[proxy]` is not given an `interface`.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus::proxy;
+/// use zbus_lockstep_macros::validate_proxy;
+///
+/// #[validate_proxy(xml: "xml")]
+/// #[proxy(interface = "org.example.Node")]
+/// trait Node {
+///     fn request_name(&self, apple: &str, orange: u32) -> zbus::Result<u32>;
+///
+///     #[zbus(property)]
+///     fn features(&self) -> zbus::Result<Vec<String>>;
+///
+///     #[zbus(signal)]
+///     fn rename(&self, old_name: &str, new_name: &str) -> zbus::Result<()>;
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn validate_proxy(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as interface::InterfaceArgs);
+    let item = parse_macro_input!(input as syn::ItemTrait);
+
+    proxy::expand(args, item)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// The string arguments are kept as literals, so that errors can point at them.
 struct ValidateArgs {
-    // Optional path to XML file
-    xml: Option<PathBuf>,
+    // Optional paths to XML directories, searched in order
+    xml: Vec<LitStr>,
 
     // Optional interface name
-    interface: Option<String>,
+    interface: Option<LitStr>,
 
     // Optional signal name
-    signal: Option<String>,
+    signal: Option<LitStr>,
+
+    // Optional patterns matching several signals
+    signals: Option<Vec<LitStr>>,
+
+    // Optional method name
+    method: Option<LitStr>,
+
+    // Optional direction of a method's arguments: `in` or `out`
+    direction: Option<Ident>,
+
+    // Optional property name
+    property: Option<LitStr>,
+
+    // Optional access of the property: `read`, `write` or `readwrite`
+    access: Option<Ident>,
+
+    // Optional selection of the signal's arguments
+    argument: Option<syn::Expr>,
+
+    // Optional shape of the signal's arguments: `flattened`, `struct` or `auto`
+    shape: Option<Ident>,
+
+    // Whether field names must follow the argument names
+    strict: Option<syn::LitBool>,
+
+    // Optional `zbus_lockstep::SignaturePolicy` expression
+    policy: Option<syn::Expr>,
+
+    // Optional name of the generated test
+    test_name: Option<Ident>,
+
+    // Optional instantiations of a generic item, such as `Event<String>`
+    instances: Vec<LitStr>,
+
+    // Optional strategy to infer the member name from the item's name
+    naming: Option<Naming>,
+
+    // Optional suffixes stripped from the item's name by some strategies
+    suffixes: Option<Vec<String>>,
+
+    // Optional patterns of the XML files to read
+    include: Option<Vec<String>>,
+
+    // Optional patterns of the XML files to skip
+    exclude: Option<Vec<String>>,
+
+    // Position among stacked `validate` attributes, set by the first of them
+    position: Option<usize>,
+}
+
+/// The name of the argument that tells a stacked `validate` attribute its position.
+const POSITION_ARG: &str = "__position";
+
+/// Add its position to each `validate` attribute left on `item`, counting from 1.
+fn number_stacked_attributes(item: &mut syn::Item) {
+    let attrs = match item {
+        syn::Item::Struct(item) => &mut item.attrs,
+        syn::Item::Enum(item) => &mut item.attrs,
+        syn::Item::Union(item) => &mut item.attrs,
+        syn::Item::Type(item) => &mut item.attrs,
+        _ => return,
+    };
+
+    // Other crates have `validate` attributes too, which must be left alone.
+    let is_validate = |attr: &syn::Attribute| {
+        let segments: Vec<String> = attr
+            .path()
+            .segments
+            .iter()
+            .map(|segment| segment.ident.to_string())
+            .collect();
+        let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+        matches!(
+            segments.as_slice(),
+            ["validate"] | ["zbus_lockstep" | "zbus_lockstep_macros", "validate"]
+        )
+    };
+
+    for (i, attr) in attrs
+        .iter_mut()
+        .filter(|attr| is_validate(attr))
+        .enumerate()
+    {
+        let position = proc_macro2::Literal::usize_unsuffixed(i + 1);
+        let position_arg = Ident::new(POSITION_ARG, proc_macro2::Span::call_site());
+
+        match &mut attr.meta {
+            syn::Meta::List(list) => {
+                let ends_with_comma = list.tokens.clone().into_iter().last().is_some_and(
+                    |token| matches!(token, proc_macro2::TokenTree::Punct(p) if p.as_char() == ','),
+                );
+                if !list.tokens.is_empty() && !ends_with_comma {
+                    list.tokens.extend(quote! { , });
+                }
+                list.tokens.extend(quote! { #position_arg: #position });
+            }
+            meta => {
+                let path = meta.path().clone();
+                *meta = syn::Meta::List(syn::MetaList {
+                    path,
+                    delimiter: syn::MacroDelimiter::Paren(syn::token::Paren::default()),
+                    tokens: quote! { #position_arg: #position },
+                });
+            }
+        }
+    }
+}
+
+/// The kind of member a type is validated against.
+#[derive(Clone, Copy)]
+enum MemberKind {
+    Signal,
+    Method(zbus_xml::ArgDirection),
+    Property,
+}
+
+impl MemberKind {
+    /// The name of the kind in error messages.
+    fn name(self) -> &'static str {
+        match self {
+            MemberKind::Signal => "signal",
+            MemberKind::Method(_) => "method",
+            MemberKind::Property => "property",
+        }
+    }
+
+    /// The `zbus_lockstep` function that retrieves the signature of a signal's or method's
+    /// arguments.
+    fn signature_lookup(self) -> proc_macro2::TokenStream {
+        match self {
+            MemberKind::Signal => quote! { zbus_lockstep::get_signal_body_type_with_shape },
+            MemberKind::Property => unreachable!("Properties have no arguments."),
+            MemberKind::Method(zbus_xml::ArgDirection::In) => {
+                quote! { zbus_lockstep::get_method_args_type_with_shape }
+            }
+            MemberKind::Method(zbus_xml::ArgDirection::Out) => {
+                quote! { zbus_lockstep::get_method_return_type_with_shape }
+            }
+        }
+    }
+
+    /// The `zbus_lockstep` function that retrieves the names of the member's arguments, and the
+    /// direction argument it takes, if any. Properties have no arguments.
+    fn arg_names_lookup(
+        self,
+    ) -> Option<(proc_macro2::TokenStream, Option<proc_macro2::TokenStream>)> {
+        match self {
+            MemberKind::Signal => Some((quote! { zbus_lockstep::get_signal_arg_names }, None)),
+            MemberKind::Property => None,
+            MemberKind::Method(direction) => {
+                let direction = match direction {
+                    zbus_xml::ArgDirection::In => quote! { zbus_lockstep::ArgDirection::In },
+                    zbus_xml::ArgDirection::Out => quote! { zbus_lockstep::ArgDirection::Out },
+                };
+                Some((
+                    quote! { zbus_lockstep::get_method_arg_names },
+                    Some(quote! { #direction, }),
+                ))
+            }
+        }
+    }
+}
+
+impl ValidateArgs {
+    /// The types to validate, with their names: the instantiations given with `as`, with
+    /// lifetimes made `'static`, or else the item itself.
+    ///
+    /// An item with only lifetime parameters is instantiated with `'static` lifetimes. An item with
+    /// type or const parameters requires `as`.
+    fn instances(&self, item: &syn::Item, item_ident: &Ident) -> Result<Vec<(String, syn::Type)>> {
+        if !self.instances.is_empty() {
+            return self
+                .instances
+                .iter()
+                .map(|lit| {
+                    let ty = lit.parse::<syn::Type>()?;
+                    Ok((lit.value(), interface::static_lifetimes(&ty)))
+                })
+                .collect();
+        }
+
+        let generics = match item {
+            syn::Item::Struct(item) => &item.generics,
+            syn::Item::Enum(item) => &item.generics,
+            syn::Item::Union(item) => &item.generics,
+            syn::Item::Type(item) => &item.generics,
+            _ => unreachable!("`validate` only accepts structs, enums, unions and type aliases"),
+        };
+
+        if generics.type_params().next().is_some() || generics.const_params().next().is_some() {
+            return Err(syn::Error::new_spanned(
+                generics,
+                format!(
+                    "`validate` cannot name a generic type, add `as: \"{item_ident}<...>\"` to \
+                     choose its instantiations"
+                ),
+            ));
+        }
+
+        // The type is built from the item's identifier, so that errors about it point there.
+        let lifetimes = generics
+            .lifetimes()
+            .map(|_| quote! { 'static })
+            .collect::<Vec<_>>();
+        if lifetimes.is_empty() {
+            return Ok(vec![(
+                item_ident.to_string(),
+                syn::parse_quote!(#item_ident),
+            )]);
+        }
+        let name = format!(
+            "{item_ident}<{}>",
+            vec!["'static"; lifetimes.len()].join(", ")
+        );
+
+        Ok(vec![(
+            name,
+            syn::parse_quote!(#item_ident<#(#lifetimes),*>),
+        )])
+    }
+
+    /// The kinds of member to validate against, in the order they are searched.
+    ///
+    /// Without `signal`, `method` or `property`, item names are searched among the signals first,
+    /// then among the properties. Item names ending in `Reply` or `Request` are then taken to be a
+    /// method's reply or arguments, so that they keep matching the signals and properties they
+    /// matched before methods were considered.
+    fn member_kinds(&self, item_name: &str) -> Result<Vec<MemberKind>> {
+        let direction = match self.direction.as_ref().map(Ident::to_string).as_deref() {
+            Some("in") => Some(zbus_xml::ArgDirection::In),
+            Some("out") => Some(zbus_xml::ArgDirection::Out),
+            _ => None,
+        };
+
+        let named = [
+            ("signal", self.signal.as_ref().map(LitStr::span)),
+            (
+                "signals",
+                self.signals
+                    .as_ref()
+                    .and_then(|patterns| patterns.first())
+                    .map(LitStr::span),
+            ),
+            ("method", self.method.as_ref().map(LitStr::span)),
+            ("property", self.property.as_ref().map(LitStr::span)),
+        ];
+        let mut named = named
+            .iter()
+            .filter_map(|(name, span)| span.map(|span| (name, span)));
+        if let (Some((first, _)), Some((second, span))) = (named.next(), named.next()) {
+            return Err(syn::Error::new(
+                span,
+                format!("`{first}:` and `{second}:` cannot be combined"),
+            ));
+        }
+
+        if self.signal.is_some() || self.signals.is_some() {
+            return Ok(vec![MemberKind::Signal]);
+        }
+        if self.property.is_some() {
+            return Ok(vec![MemberKind::Property]);
+        }
+
+        let suffix_direction = if item_name.ends_with("Reply") {
+            Some(zbus_xml::ArgDirection::Out)
+        } else if item_name.ends_with("Request") {
+            Some(zbus_xml::ArgDirection::In)
+        } else {
+            None
+        };
+
+        if self.method.is_some() || direction.is_some() {
+            let direction = direction
+                .or(suffix_direction)
+                .unwrap_or(zbus_xml::ArgDirection::In);
+            return Ok(vec![MemberKind::Method(direction)]);
+        }
+
+        if self.access.is_some() {
+            return Ok(vec![MemberKind::Property]);
+        }
+
+        let mut kinds = vec![MemberKind::Signal, MemberKind::Property];
+        kinds.extend(suffix_direction.map(MemberKind::Method));
+        Ok(kinds)
+    }
+
+    /// Reject the arguments that do not apply to the kind of member found.
+    fn check_applicable(&self, kind: MemberKind) -> Result<()> {
+        let not_applicable = |ident: &Ident, applies_to: &str| {
+            Err(syn::Error::new(
+                ident.span(),
+                format!("`{ident}:` only applies to {applies_to}"),
+            ))
+        };
+
+        match kind {
+            MemberKind::Signal | MemberKind::Method(_) => {
+                if let Some(access) = &self.access {
+                    return not_applicable(access, "properties");
+                }
+                if let (MemberKind::Signal, Some(direction)) = (kind, &self.direction) {
+                    return not_applicable(direction, "methods");
+                }
+            }
+            MemberKind::Property => {
+                if let Some(direction) = &self.direction {
+                    return not_applicable(direction, "methods");
+                }
+                let argument = self
+                    .argument
+                    .as_ref()
+                    .map(|argument| ("argument", argument.span()));
+                let shape = self.shape.as_ref().map(|shape| ("shape", shape.span()));
+                let strict = self
+                    .strict
+                    .as_ref()
+                    .filter(|strict| strict.value)
+                    .map(|strict| ("strict", strict.span()));
+                if let Some((name, span)) = argument.or(shape).or(strict) {
+                    return Err(syn::Error::new(
+                        span,
+                        format!("`{name}:` does not apply to properties"),
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The `zbus_lockstep::PropertyAccess` expression for the `access` argument, if any.
+    fn access_tokens(&self) -> Option<proc_macro2::TokenStream> {
+        self.access
+            .as_ref()
+            .map(|access| match access.to_string().as_str() {
+                "read" => quote! { zbus_lockstep::PropertyAccess::Read },
+                "write" => quote! { zbus_lockstep::PropertyAccess::Write },
+                _ => quote! { zbus_lockstep::PropertyAccess::ReadWrite },
+            })
+    }
+
+    /// The `zbus_lockstep::BodyShape` expression for the `shape` argument, or else the configured
+    /// shape.
+    fn shape_tokens(&self, config: &Config) -> proc_macro2::TokenStream {
+        let shape = self
+            .shape
+            .as_ref()
+            .and_then(|ident| Shape::ALL.into_iter().find(|shape| ident == shape.name()))
+            .or(config.shape);
+        match shape {
+            Some(Shape::Flattened) => quote! { zbus_lockstep::BodyShape::Flattened },
+            Some(Shape::Struct) => quote! { zbus_lockstep::BodyShape::Struct },
+            Some(Shape::Auto) | None => quote! { zbus_lockstep::BodyShape::Auto },
+        }
+    }
+
+    /// The `zbus_lockstep::SignaturePolicy` expression for the `policy` argument, or else the
+    /// configured policies.
+    fn policy_tokens(&self, config: &Config) -> proc_macro2::TokenStream {
+        let policy_tokens = |policy: &Policy| match policy {
+            Policy::Exact => quote! { zbus_lockstep::SignaturePolicy::Exact },
+            Policy::AllowSignDifference => {
+                quote! { zbus_lockstep::SignaturePolicy::AllowSignDifference }
+            }
+            Policy::AllowVariantWidening => {
+                quote! { zbus_lockstep::SignaturePolicy::AllowVariantWidening }
+            }
+        };
+
+        match (&self.policy, config.policy.as_deref()) {
+            (Some(policy), _) => quote! { #policy },
+            (None, Some([policy])) => policy_tokens(policy),
+            (None, Some(policies)) => {
+                let policies = policies.iter().map(policy_tokens);
+                quote! { zbus_lockstep::SignaturePolicy::Any(vec![#(#policies),*]) }
+            }
+            (None, None) => quote! { zbus_lockstep::SignaturePolicy::Exact },
+        }
+    }
+
+    /// The `zbus_lockstep::ArgSelector` expression for the `argument` argument.
+    fn argument_tokens(&self) -> proc_macro2::TokenStream {
+        match &self.argument {
+            None => quote! { zbus_lockstep::ArgSelector::All },
+            Some(syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Int(index),
+                ..
+            })) => quote! { zbus_lockstep::ArgSelector::Index(#index) },
+            Some(syn::Expr::Range(range)) => {
+                let start = range
+                    .start
+                    .as_ref()
+                    .map_or_else(|| quote! { 0 }, |e| quote! { #e });
+                match (&range.limits, &range.end) {
+                    (syn::RangeLimits::HalfOpen(_), Some(end)) => {
+                        quote! { zbus_lockstep::ArgSelector::Range(#start..#end) }
+                    }
+                    (syn::RangeLimits::Closed(_), Some(end)) => {
+                        quote! { zbus_lockstep::ArgSelector::from(#start..=#end) }
+                    }
+                    (_, None) => quote! { zbus_lockstep::ArgSelector::RangeFrom(#start) },
+                }
+            }
+            Some(syn::Expr::Array(names)) => {
+                let names = names.elems.iter();
+                quote! { zbus_lockstep::ArgSelector::Names(vec![#(#names.to_string()),*]) }
+            }
+            Some(name) => quote! { zbus_lockstep::ArgSelector::Name(#name.to_string()) },
+        }
+    }
+}
+
+/// Parse the value of `argument:`: a name, a position, a range of positions or a list of names.
+fn parse_argument(input: ParseStream) -> Result<syn::Expr> {
+    let expr = input.parse::<syn::Expr>()?;
+
+    let is_int = |expr: &syn::Expr| {
+        matches!(
+            expr,
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Int(_),
+                ..
+            })
+        )
+    };
+    let is_str = |expr: &syn::Expr| {
+        matches!(
+            expr,
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(_),
+                ..
+            })
+        )
+    };
+
+    let valid = match &expr {
+        syn::Expr::Range(range) => {
+            range.start.as_deref().is_none_or(is_int) && range.end.as_deref().is_none_or(is_int)
+        }
+        syn::Expr::Array(names) => names.elems.iter().all(is_str),
+        expr => is_int(expr) || is_str(expr),
+    };
+
+    if valid {
+        Ok(expr)
+    } else {
+        Err(syn::Error::new_spanned(
+            expr,
+            "Expected an argument name, a position, a range of positions or a list of names",
+        ))
+    }
+}
+
+/// Parse a string literal or a bracketed list of string literals.
+fn parse_lit_strs(input: ParseStream) -> Result<Vec<LitStr>> {
+    if input.peek(syn::token::Bracket) {
+        let content;
+        syn::bracketed!(content in input);
+        let lits = content.parse_terminated(|input| input.parse::<LitStr>(), Token![,])?;
+        Ok(lits.into_iter().collect())
+    } else {
+        Ok(vec![input.parse::<LitStr>()?])
+    }
 }
 
 impl syn::parse::Parse for ValidateArgs {
     fn parse(input: ParseStream) -> Result<Self> {
-        let mut xml = None;
+        let mut xml = Vec::new();
         let mut interface = None;
         let mut signal = None;
+        let mut signals = None;
+        let mut method = None;
+        let mut direction = None;
+        let mut property = None;
+        let mut access = None;
+        let mut argument = None;
+        let mut shape = None;
+        let mut strict = None;
+        let mut policy = None;
+        let mut test_name = None;
+        let mut instances = Vec::new();
+        let mut naming = None;
+        let mut suffixes = None;
+        let mut include = None;
+        let mut exclude = None;
+        let mut position = None;
 
         while !input.is_empty() {
-            let ident = input.parse::<Ident>()?;
+            // `as` is a keyword.
+            let ident = Ident::parse_any(input)?;
             match ident.to_string().as_str() {
                 "xml" => {
                     input.parse::<Token![:]>()?;
-                    let lit = input.parse::<LitStr>()?;
-                    xml = Some(PathBuf::from(lit.value()));
+                    xml.push(input.parse::<LitStr>()?);
                 }
                 "interface" => {
                     input.parse::<Token![:]>()?;
-                    let lit = input.parse::<LitStr>()?;
-                    interface = Some(lit.value());
+                    interface = Some(input.parse::<LitStr>()?);
                 }
                 "signal" => {
+                    input.parse::<Token![:]>()?;
+                    signal = Some(input.parse::<LitStr>()?);
+                }
+                "signals" => {
+                    input.parse::<Token![:]>()?;
+                    signals = Some(parse_lit_strs(input)?);
+                }
+                "method" => {
+                    input.parse::<Token![:]>()?;
+                    method = Some(input.parse::<LitStr>()?);
+                }
+                "direction" => {
+                    input.parse::<Token![:]>()?;
+                    // `in` is a keyword, so accept any identifier and check it afterwards.
+                    let ident = Ident::parse_any(input)?;
+                    if !matches!(ident.to_string().as_str(), "in" | "out") {
+                        return Err(syn::Error::new(
+                            ident.span(),
+                            format!("Unexpected direction: {ident}, expected in or out"),
+                        ));
+                    }
+                    direction = Some(ident);
+                }
+                "property" => {
+                    input.parse::<Token![:]>()?;
+                    property = Some(input.parse::<LitStr>()?);
+                }
+                "access" => {
+                    input.parse::<Token![:]>()?;
+                    let ident = input.parse::<Ident>()?;
+                    if !matches!(ident.to_string().as_str(), "read" | "write" | "readwrite") {
+                        return Err(syn::Error::new(
+                            ident.span(),
+                            format!(
+                                "Unexpected access: {ident}, expected read, write or readwrite"
+                            ),
+                        ));
+                    }
+                    access = Some(ident);
+                }
+                "argument" => {
+                    input.parse::<Token![:]>()?;
+                    argument = Some(parse_argument(input)?);
+                }
+                "shape" => {
+                    input.parse::<Token![:]>()?;
+                    // `struct` is a keyword, so accept any identifier and check it afterwards.
+                    let ident = Ident::parse_any(input)?;
+                    if !Shape::ALL.iter().any(|shape| ident == shape.name()) {
+                        return Err(syn::Error::new(
+                            ident.span(),
+                            format!(
+                                "Unexpected shape: {ident}, expected flattened, struct or auto"
+                            ),
+                        ));
+                    }
+                    shape = Some(ident);
+                }
+                "strict" => {
+                    input.parse::<Token![:]>()?;
+                    strict = Some(input.parse::<syn::LitBool>()?);
+                }
+                "policy" => {
+                    input.parse::<Token![:]>()?;
+                    policy = Some(input.parse::<syn::Expr>()?);
+                }
+                "test_name" => {
                     input.parse::<Token![:]>()?;
                     let lit = input.parse::<LitStr>()?;
-                    signal = Some(lit.value());
+                    let ident = syn::parse_str::<Ident>(&lit.value()).map_err(|_| {
+                        syn::Error::new(
+                            lit.span(),
+                            format!("Expected a function name, found \"{}\"", lit.value()),
+                        )
+                    })?;
+                    test_name = Some(Ident::new(&ident.to_string(), lit.span()));
+                }
+                "as" => {
+                    input.parse::<Token![:]>()?;
+                    instances = parse_lit_strs(input)?;
+                }
+                "naming" => {
+                    input.parse::<Token![:]>()?;
+                    let ident = input.parse::<Ident>()?;
+                    naming = Some(naming::parse(&ident.to_string(), ident.span())?);
+                }
+                "suffixes" => {
+                    input.parse::<Token![:]>()?;
+                    let lits = parse_lit_strs(input)?;
+                    suffixes = Some(lits.iter().map(LitStr::value).collect());
+                }
+                "include" => {
+                    input.parse::<Token![:]>()?;
+                    let lits = parse_lit_strs(input)?;
+                    include = Some(lits.iter().map(LitStr::value).collect());
+                }
+                "exclude" => {
+                    input.parse::<Token![:]>()?;
+                    let lits = parse_lit_strs(input)?;
+                    exclude = Some(lits.iter().map(LitStr::value).collect());
+                }
+                name if name == POSITION_ARG => {
+                    input.parse::<Token![:]>()?;
+                    if position.is_some() {
+                        return Err(syn::Error::new(
+                            ident.span(),
+                            format!("Duplicate argument: {POSITION_ARG}"),
+                        ));
+                    }
+                    position = Some(input.parse::<syn::LitInt>()?.base10_parse()?);
                 }
                 _ => {
                     return Err(syn::Error::new(
@@ -337,89 +1717,96 @@ impl syn::parse::Parse for ValidateArgs {
             xml,
             interface,
             signal,
+            signals,
+            method,
+            direction,
+            property,
+            access,
+            argument,
+            shape,
+            strict,
+            policy,
+            test_name,
+            instances,
+            naming,
+            suffixes,
+            include,
+            exclude,
+            position,
         })
     }
 }
 
-/// Try to resolve an XML definitions directory.
-///
-/// Matching logic, in order:
-///
-/// 1. Environment variable (`LOCKSTEP_XML_PATH`) overrides everything.
-/// 2. Provided argument (if `Some`) overrides the default location.
-/// 3. Default location fallbacks (only searched if 1 and 2 are not set).
-fn resolve_xml_path(xml: Option<&str>) -> Result<PathBuf> {
-    if let Ok(env_path) = std::env::var("LOCKSTEP_XML_PATH") {
-        let xml_path = PathBuf::from(env_path);
-        return xml_path.canonicalize().map_err(|e| {
-            syn::Error::new(
-                proc_macro2::Span::call_site(),
-                format!(
-                    "Failed to canonicalize LOCKSTEP_XML_PATH '{}': {}",
-                    xml_path.display(),
-                    e
-                ),
-            )
-        });
-    }
+/// An XML file of one of the definitions directories.
+struct XmlFile {
+    /// The definitions directory the file was found in.
+    root: PathBuf,
+    path: PathBuf,
+    xml: String,
+}
 
-    if let Some(arg_path) = xml {
-        let xml_path = PathBuf::from(arg_path);
-        return xml_path.canonicalize().map_err(|e| {
-            syn::Error::new(
-                proc_macro2::Span::call_site(),
-                format!(
-                    "Failed to canonicalize provided XML path '{}': {}",
-                    xml_path.display(),
-                    e
-                ),
-            )
-        });
+impl XmlFile {
+    /// Where the file came from: its path within its root, and the root.
+    fn source(&self) -> String {
+        source_of(&self.root, &self.path)
     }
+}
 
-    // Try fallback paths:
+/// `path` within `root`, and `root`, for messages.
+fn source_of(root: &std::path::Path, path: &std::path::Path) -> String {
+    format!(
+        "\"{}\" of \"{}\"",
+        path.strip_prefix(root).unwrap_or(path).display(),
+        root.display()
+    )
+}
 
-    let current_dir = std::env::var("CARGO_MANIFEST_DIR")
-        .map(PathBuf::from)
-        .map_err(|e| {
-            syn::Error::new(
-                proc_macro2::Span::call_site(),
-                format!("CARGO_MANIFEST_DIR environment variable is not set: {e}"),
-            )
-        })?;
-
-    let crate_name = std::env::var("CARGO_PKG_NAME").unwrap_or_else(|_| String::from("unknown"));
-
-    // Definieer de standaardpaden die we willen proberen:
-    let paths_to_try = [
-        current_dir.join("xml"),                   // ./xml
-        current_dir.join("XML"),                   // ./XML
-        current_dir.join("../xml"),                // ../xml
-        current_dir.join("../XML"),                // ../XML
-        current_dir.join(&crate_name).join("xml"), // ./<crate_name>/xml
-        current_dir.join(&crate_name).join("XML"), // ./<crate_name>/XML
-    ];
-
-    for path in paths_to_try {
-        if path.exists() {
-            return path.canonicalize().map_err(|e| {
+/// Read the XML files of the definitions directories that `filter` accepts, see
+/// [`resolve_xml_path`].
+///
+/// Returns the files of each directory in turn, each sorted by path. Errors point at the `xml`
+/// argument they concern, or at the call site.
+fn read_xml_files(xml: &[LitStr], filter: &XmlFilter, config: &Config) -> Result<Vec<XmlFile>> {
+    let span = xml
+        .first()
+        .map_or_else(proc_macro2::Span::call_site, LitStr::span);
+
+    let mut xml_files = Vec::new();
+    for root in resolve_xml_path(xml, config)? {
+        for path in
+            discover_xml_files(&root, filter).map_err(|e| syn::Error::new(span, e.to_string()))?
+        {
+            let xml = std::fs::read_to_string(&path).map_err(|e| {
                 syn::Error::new(
-                    proc_macro2::Span::call_site(),
-                    format!(
-                        "Failed to canonicalize default XML path '{}': {}",
-                        path.display(),
-                        e
-                    ),
+                    span,
+                    format!("Failed to read XML file: \"{}\" Err: {e}", path.display()),
                 )
+            })?;
+            xml_files.push(XmlFile {
+                root: root.clone(),
+                path,
+                xml,
             });
         }
     }
 
-    Err(syn::Error::new(
-        proc_macro2::Span::call_site(),
-        format!(
-            "No XML path provided and default XML path not found. Current directory: \"{}\"",
-            current_dir.display()
-        ),
-    ))
+    Ok(xml_files)
+}
+
+/// Resolve the XML definitions directories, see `zbus_lockstep_common::roots::resolve_xml_roots`.
+///
+/// Errors point at the argument they concern, or at the call site.
+fn resolve_xml_path(xml: &[LitStr], config: &Config) -> Result<Vec<PathBuf>> {
+    let args: Vec<String> = xml.iter().map(LitStr::value).collect();
+
+    resolve_xml_roots(&args, Some(config)).map_err(|e| {
+        let span = match &e {
+            RootError::Canonicalize {
+                origin: RootOrigin::Argument(i),
+                ..
+            } => xml[*i].span(),
+            _ => proc_macro2::Span::call_site(),
+        };
+        syn::Error::new(span, e.to_string())
+    })
 }
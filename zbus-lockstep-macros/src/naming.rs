@@ -0,0 +1,88 @@
+//! Strategies for inferring the XML member an item validates from the item's name.
+
+use proc_macro2::Span;
+pub(crate) use zbus_lockstep_common::config::Naming;
+
+use crate::{Result, config::Config, interface::pascal_case};
+
+/// The environment variable that sets the strategy for all `validate` attributes of a crate.
+const NAMING_ENV: &str = "LOCKSTEP_NAMING";
+
+/// The environment variable that sets the suffixes stripped by [`Naming::StripSuffix`], as a
+/// comma separated list.
+const SUFFIXES_ENV: &str = "LOCKSTEP_SUFFIXES";
+
+/// The suffixes stripped by [`Naming::StripSuffix`] unless configured otherwise.
+const DEFAULT_SUFFIXES: [&str; 4] = ["Event", "Signal", "Reply", "Request"];
+
+/// The strategy named `name`, with the error pointing at `span`.
+pub(crate) fn parse(name: &str, span: Span) -> Result<Naming> {
+    Naming::ALL
+        .into_iter()
+        .find(|naming| naming.name() == name)
+        .ok_or_else(|| {
+            let names = Naming::ALL.map(Naming::name).join(", ");
+            syn::Error::new(
+                span,
+                format!("Unexpected naming: {name}, expected one of {names}"),
+            )
+        })
+}
+
+/// The crate-wide strategy from `LOCKSTEP_NAMING`, else `naming`, else the one from the
+/// configuration, or `Contains`.
+pub(crate) fn resolve(naming: Option<Naming>, config: &Config) -> Result<Naming> {
+    match std::env::var(NAMING_ENV) {
+        Ok(name) => parse(name.trim(), Span::call_site())
+            .map_err(|e| syn::Error::new(e.span(), format!("{NAMING_ENV}: {e}"))),
+        Err(_) => Ok(naming.or(config.naming).unwrap_or(Naming::Contains)),
+    }
+}
+
+/// How well `item_name` matches the member `member_name` under `naming`, if at all.
+///
+/// Of several matching members, only the best matches are considered. All matches are equally
+/// good, except for `Longest`, which prefers longer member names.
+pub(crate) fn score(
+    naming: Naming,
+    item_name: &str,
+    suffixes: &[String],
+    member_name: &str,
+) -> Option<usize> {
+    let matches = match naming {
+        Naming::Contains => item_name.contains(member_name),
+        Naming::Exact => item_name == member_name,
+        Naming::StripSuffix => strip_suffix(item_name, suffixes) == member_name,
+        Naming::Longest => {
+            return item_name.contains(member_name).then_some(member_name.len());
+        }
+        Naming::SnakeCase => strip_suffix(item_name, suffixes) == pascal_case(member_name),
+    };
+
+    matches.then_some(0)
+}
+
+/// The crate-wide suffixes from `LOCKSTEP_SUFFIXES`, else `suffixes`, else the ones from the
+/// configuration, or the default ones.
+pub(crate) fn resolve_suffixes(suffixes: Option<Vec<String>>, config: &Config) -> Vec<String> {
+    match std::env::var(SUFFIXES_ENV) {
+        Ok(suffixes) => suffixes
+            .split(',')
+            .map(str::trim)
+            .filter(|suffix| !suffix.is_empty())
+            .map(ToOwned::to_owned)
+            .collect(),
+        Err(_) => suffixes
+            .or_else(|| config.suffixes.clone())
+            .unwrap_or_else(|| DEFAULT_SUFFIXES.map(ToOwned::to_owned).to_vec()),
+    }
+}
+
+/// `name` without the first of `suffixes` it ends with.
+fn strip_suffix<'a>(name: &'a str, suffixes: &[String]) -> &'a str {
+    suffixes
+        .iter()
+        .find_map(|suffix| name.strip_suffix(suffix.as_str()))
+        .filter(|stripped| !stripped.is_empty())
+        .unwrap_or(name)
+}
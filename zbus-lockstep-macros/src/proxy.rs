@@ -0,0 +1,46 @@
+//! Validation of zbus `#[proxy]` traits.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{ItemTrait, TraitItem};
+
+use crate::{
+    Result, config,
+    interface::{
+        InterfaceArgs, Side, find_interface_file, interface_name_of, member_test, members_of,
+    },
+};
+
+/// Expand `#[validate_proxy]` on a trait.
+///
+/// Unlike an impl block, a proxy commonly covers only part of an interface, so the XML members
+/// the trait lacks are not reported.
+pub(crate) fn expand(args: InterfaceArgs, item: ItemTrait) -> Result<TokenStream> {
+    let side = Side::Proxy;
+    let interface_lit = match args.interface {
+        Some(interface_lit) => interface_lit,
+        None => interface_name_of(&item.attrs, side)?,
+    };
+    let config = config::load()?;
+    let xml_file = find_interface_file(&args.xml, &interface_lit, &config)?;
+    let interface_name = interface_lit.value();
+
+    let methods = item.items.iter().filter_map(|trait_item| match trait_item {
+        TraitItem::Fn(method) => Some((method.attrs.as_slice(), &method.sig)),
+        _ => None,
+    });
+    let members = members_of(methods, side)?;
+
+    let tests = members
+        .iter()
+        .map(|member| member_test(member, &item.ident, &interface_name, &xml_file, side));
+    let config_tracking = config::tracking_item(&config);
+
+    Ok(quote! {
+        #item
+
+        #config_tracking
+
+        #(#tests)*
+    })
+}
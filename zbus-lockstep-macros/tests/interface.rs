@@ -0,0 +1,104 @@
+#![allow(unnameable_test_items, dead_code)]
+
+use zbus::{interface, object_server::SignalEmitter};
+use zbus_lockstep_macros::validate_interface;
+use zvariant::OwnedObjectPath;
+
+#[test]
+fn test_validate_interface() {
+    struct Node;
+
+    #[validate_interface]
+    #[interface(name = "org.example.Node")]
+    impl Node {
+        fn request_name(&self, apple: String, orange: u32) -> zbus::fdo::Result<u32> {
+            Ok(apple.len() as u32 + orange)
+        }
+
+        fn ping(&self) {}
+
+        #[zbus(property)]
+        fn features(&self) -> Vec<String> {
+            Vec::new()
+        }
+
+        #[zbus(signal)]
+        async fn add_node(
+            emitter: &SignalEmitter<'_>,
+            node_added: (String, OwnedObjectPath),
+        ) -> zbus::Result<()>;
+
+        #[zbus(signal)]
+        async fn alert(
+            emitter: &SignalEmitter<'_>,
+            urgent: bool,
+            color: &str,
+            volume: f64,
+        ) -> zbus::Result<()>;
+
+        #[zbus(signal)]
+        async fn remove_node(
+            emitter: &SignalEmitter<'_>,
+            node_removed: (String, OwnedObjectPath),
+        ) -> zbus::Result<()>;
+
+        #[zbus(signal)]
+        async fn level(emitter: &SignalEmitter<'_>, level: i32) -> zbus::Result<()>;
+
+        #[zbus(signal)]
+        async fn rename(
+            emitter: &SignalEmitter<'_>,
+            old_name: &str,
+            new_name: &str,
+        ) -> zbus::Result<()>;
+
+        #[zbus(signal)]
+        async fn reset(emitter: &SignalEmitter<'_>) -> zbus::Result<()>;
+
+        #[zbus(signal, name = "U32AsEnum")]
+        async fn u32_as_enum(emitter: &SignalEmitter<'_>, my_type: u32) -> zbus::Result<()>;
+    }
+
+    test_Node_RequestName_method();
+    test_Node_Ping_method();
+    test_Node_Features_property();
+    test_Node_AddNode_signal();
+    test_Node_Alert_signal();
+    test_Node_RemoveNode_signal();
+    test_Node_Level_signal();
+    test_Node_Rename_signal();
+    test_Node_Reset_signal();
+    test_Node_U32AsEnum_signal();
+    test_Node_interface_members();
+}
+
+#[test]
+#[should_panic(expected = "`PartialNode` does not implement these members of `org.example.Node`")]
+fn test_validate_interface_reports_missing_members() {
+    struct PartialNode;
+
+    #[validate_interface(xml: "xml")]
+    #[interface(name = "org.example.Node")]
+    impl PartialNode {
+        fn ping(&self) {}
+    }
+
+    test_PartialNode_Ping_method();
+    test_PartialNode_interface_members();
+}
+
+#[test]
+#[should_panic(expected = "field 1 (`orange`): expected `u`, found `s`")]
+fn test_validate_interface_reports_mismatching_argument() {
+    struct MismatchedNode;
+
+    #[validate_interface(interface: "org.example.Node")]
+    #[interface(name = "org.example.MismatchedNode")]
+    impl MismatchedNode {
+        fn request_name(&self, apple: String, orange: String) -> u32 {
+            (apple.len() + orange.len()) as u32
+        }
+    }
+
+    test_MismatchedNode_RequestName_method();
+}
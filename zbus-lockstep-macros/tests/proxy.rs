@@ -0,0 +1,64 @@
+#![allow(unnameable_test_items, dead_code)]
+
+use zbus::proxy;
+use zbus_lockstep_macros::validate_proxy;
+use zvariant::OwnedObjectPath;
+
+#[test]
+fn test_validate_proxy() {
+    #[validate_proxy]
+    #[proxy(interface = "org.example.Node")]
+    trait Node {
+        fn request_name(&self, apple: &str, orange: u32) -> zbus::Result<u32>;
+
+        fn ping(&self) -> zbus::Result<()>;
+
+        #[zbus(property)]
+        fn features(&self) -> zbus::Result<Vec<String>>;
+
+        #[zbus(signal)]
+        fn add_node(&self, node_added: (String, OwnedObjectPath)) -> zbus::Result<()>;
+
+        #[zbus(signal)]
+        fn rename(&self, old_name: &str, new_name: &str) -> zbus::Result<()>;
+
+        #[zbus(signal)]
+        fn reset(&self) -> zbus::Result<()>;
+    }
+
+    test_Node_RequestName_method();
+    test_Node_Ping_method();
+    test_Node_Features_property();
+    test_Node_AddNode_signal();
+    test_Node_Rename_signal();
+    test_Node_Reset_signal();
+}
+
+#[test]
+#[should_panic(expected = "Property \"Features\" has access \"read\", expected \"readwrite\".")]
+fn test_validate_proxy_reports_setter_of_read_only_property() {
+    #[validate_proxy(xml: "xml")]
+    #[proxy(interface = "org.example.Node")]
+    trait WritableNode {
+        #[zbus(property)]
+        fn features(&self) -> zbus::Result<Vec<String>>;
+
+        #[zbus(property)]
+        fn set_features(&self, features: &[String]) -> zbus::Result<()>;
+    }
+
+    test_WritableNode_Features_property();
+}
+
+#[test]
+#[should_panic(expected = "expected `u`, found `i`")]
+fn test_validate_proxy_reports_mismatching_reply() {
+    #[validate_proxy(interface: "org.example.Node")]
+    #[proxy(interface = "org.example.MismatchedNode")]
+    trait MismatchedNode {
+        #[zbus(name = "RequestName")]
+        fn request(&self, apple: &str, orange: u32) -> zbus::Result<i32>;
+    }
+
+    test_MismatchedNode_RequestName_method();
+}
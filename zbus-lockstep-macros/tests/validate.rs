@@ -94,3 +94,476 @@ fn test_validate_macro_non_matching_signature_of_type() {
     // now call the test generated by the `validate` macro
     test_RemoveNode_type_signature();
 }
+
+#[test]
+fn test_validate_macro_argument_by_name() {
+    #[validate(signal: "Alert", argument: "volume")]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct Volume(f64);
+
+    test_Volume_type_signature();
+}
+
+#[test]
+fn test_validate_macro_argument_by_position() {
+    #[validate(signal: "Alert", argument: 0)]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct Urgency(bool);
+
+    test_Urgency_type_signature();
+}
+
+#[test]
+fn test_validate_macro_argument_by_range() {
+    #[validate(signal: "Alert", argument: 1..=2)]
+    #[derive(Debug, Type)]
+    struct AlertSound {
+        _color: String,
+        _volume: f64,
+    }
+
+    test_AlertSound_type_signature();
+}
+
+#[test]
+fn test_validate_macro_argument_by_open_range() {
+    #[validate(signal: "Alert", argument: 1..)]
+    #[derive(Debug, Type)]
+    struct AlertTone {
+        _color: String,
+        _volume: f64,
+    }
+
+    test_AlertTone_type_signature();
+}
+
+#[test]
+fn test_validate_macro_argument_by_names() {
+    #[validate(signal: "Alert", argument: ["volume", "urgent"])]
+    #[derive(Debug, Type)]
+    struct LoudAlert {
+        _volume: f64,
+        _urgent: bool,
+    }
+
+    test_LoudAlert_type_signature();
+}
+
+#[test]
+fn test_validate_macro_shape() {
+    #[validate(signal: "U32AsEnum", shape: struct)]
+    #[derive(Debug, Type)]
+    struct U32AsEnumStruct {
+        _my_type: u32,
+    }
+
+    #[validate(signal: "U32AsEnum", shape: flattened)]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct U32AsEnumValue(u32);
+
+    // `auto` accepts both.
+    #[validate(signal: "U32AsEnum")]
+    #[derive(Debug, Type)]
+    struct U32AsEnumAuto {
+        _my_type: u32,
+    }
+
+    test_U32AsEnumStruct_type_signature();
+    test_U32AsEnumValue_type_signature();
+    test_U32AsEnumAuto_type_signature();
+}
+
+#[test]
+fn test_validate_macro_unit_types() {
+    #[validate(signal: "Reset")]
+    #[derive(Debug, Type)]
+    struct ResetSignal;
+
+    #[validate(signal: "Reset")]
+    type ResetBody = ();
+
+    test_ResetSignal_type_signature();
+    test_ResetBody_type_signature();
+}
+
+#[test]
+#[should_panic(expected = "field 1 (`path`): expected `o`, found `s`")]
+fn test_validate_macro_reports_mismatching_field() {
+    #[validate(signal: "RemoveNode")]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct RemoveNodeWithStringPath {
+        name: String,
+        path: String,
+    }
+
+    test_RemoveNodeWithStringPath_type_signature();
+}
+
+#[test]
+fn test_validate_macro_strict() {
+    #[validate(signal: "Alert", strict: true)]
+    #[derive(Debug, Type)]
+    struct StrictAlert {
+        _urgent: bool,
+        _color: String,
+        _volume: f64,
+    }
+
+    // The fields belong to the single argument's structure, names are not compared.
+    #[validate(signal: "AddNode", strict: true)]
+    #[derive(Debug, Type)]
+    struct StrictAddNode {
+        _name: String,
+        _path: zvariant::OwnedObjectPath,
+    }
+
+    test_StrictAlert_type_signature();
+    test_StrictAddNode_type_signature();
+}
+
+#[test]
+#[should_panic(expected = "field `old_name` is at position 1, but the argument is at position 0")]
+fn test_validate_macro_strict_reports_swapped_fields() {
+    #[validate(signal: "Rename", strict: true)]
+    #[derive(Debug, Type)]
+    struct SwappedRename {
+        _new_name: String,
+        _old_name: String,
+    }
+
+    test_SwappedRename_type_signature();
+}
+
+#[test]
+fn test_validate_macro_policy() {
+    use zbus_lockstep::SignaturePolicy;
+
+    #[validate(signal: "Level", policy: SignaturePolicy::AllowSignDifference)]
+    #[derive(Debug, Type)]
+    #[repr(u32)]
+    enum UnsignedLevel {
+        _Low,
+        _High,
+    }
+
+    #[validate(
+        signal: "Alert",
+        argument: "color",
+        policy: SignaturePolicy::AllowVariantWidening
+    )]
+    type AnyColor = zvariant::OwnedValue;
+
+    test_UnsignedLevel_type_signature();
+    test_AnyColor_type_signature();
+}
+
+#[test]
+#[should_panic(expected = "signature: expected `i`, found `u`")]
+fn test_validate_macro_exact_policy_rejects_sign_difference() {
+    #[validate(signal: "Level")]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct UnsignedLevelSignal(u32);
+
+    test_UnsignedLevelSignal_type_signature();
+}
+
+#[test]
+fn test_validate_macro_method() {
+    #[validate(method: "RequestName", direction: in, strict: true)]
+    #[derive(Debug, Type)]
+    struct RequestNameArgs {
+        _apple: String,
+        _orange: u32,
+    }
+
+    #[validate(method: "RequestName", direction: out)]
+    #[derive(Debug, Type)]
+    struct Grape {
+        _grape: u32,
+    }
+
+    // A method without arguments has the unit signature.
+    #[validate(method: "Ping", direction: in)]
+    #[derive(Debug, Type)]
+    struct PingArgs;
+
+    test_RequestNameArgs_type_signature();
+    test_Grape_type_signature();
+    test_PingArgs_type_signature();
+}
+
+#[test]
+fn test_validate_macro_method_discovery() {
+    #[validate]
+    #[derive(Debug, Type)]
+    struct RequestNameReply {
+        _grape: u32,
+    }
+
+    #[validate(argument: "orange")]
+    #[derive(Debug, Type)]
+    struct RequestNameRequest {
+        _orange: u32,
+    }
+
+    test_RequestNameReply_type_signature();
+    test_RequestNameRequest_type_signature();
+}
+
+#[test]
+fn test_validate_macro_reply_suffix_prefers_signals() {
+    // `Alert` is a signal, so the `Reply` suffix does not make this a method's reply.
+    #[validate]
+    #[derive(Debug, Type)]
+    struct AlertReply {
+        _urgent: bool,
+        _color: String,
+        _volume: f64,
+    }
+
+    test_AlertReply_type_signature();
+}
+
+#[test]
+#[should_panic(expected = "field 1 (`orange`): expected `u`, found `s`")]
+fn test_validate_macro_method_reports_mismatching_field() {
+    #[validate(method: "RequestName", direction: in)]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct StringlyRequestName {
+        apple: String,
+        orange: String,
+    }
+
+    test_StringlyRequestName_type_signature();
+}
+
+#[test]
+fn test_validate_macro_property() {
+    // Inferred from the type name.
+    #[validate]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct Features(Vec<String>);
+
+    #[validate(property: "Features", access: read)]
+    type FeatureList = Vec<String>;
+
+    test_Features_type_signature();
+    test_FeatureList_type_signature();
+}
+
+#[test]
+#[should_panic(expected = "Property \"Features\" has access \"read\", expected \"readwrite\".")]
+fn test_validate_macro_property_reports_access_mismatch() {
+    #[validate(property: "Features", access: readwrite)]
+    type WritableFeatures = Vec<String>;
+
+    test_WritableFeatures_type_signature();
+}
+
+#[test]
+fn test_validate_macro_signals() {
+    #[validate(signals: "org.example.Node:*Node")]
+    #[derive(Debug, Type)]
+    struct NodeEvent {
+        _name: String,
+        _path: OwnedObjectPath,
+    }
+
+    #[validate(signals: ["AddNode", "Remove?ode"])]
+    #[derive(Debug, Type)]
+    struct NodeChange {
+        _name: String,
+        _path: OwnedObjectPath,
+    }
+
+    test_NodeEvent_type_signature();
+    test_NodeChange_type_signature();
+}
+
+#[test]
+#[should_panic(expected = "org.example.Node.Rename: Signatures differ")]
+fn test_validate_macro_signals_reports_each_mismatching_signal() {
+    #[validate(signals: ["AddNode", "Rename"])]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct NodeOrRename {
+        name: String,
+        path: OwnedObjectPath,
+    }
+
+    test_NodeOrRename_type_signature();
+}
+
+#[test]
+fn test_validate_macro_stacked() {
+    #[validate(signal: "U32AsEnum")]
+    #[validate(method: "RequestName", direction: out)]
+    #[validate]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct RequestNameReply(u32);
+
+    #[validate(signal: "Level", test_name: "test_level_is_signed")]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct SignedLevel(i32);
+
+    test_RequestNameReply_type_signature();
+    test_RequestNameReply_type_signature_1();
+    test_RequestNameReply_type_signature_2();
+    test_level_is_signed();
+}
+
+#[test]
+fn test_validate_macro_stacked_four_times() {
+    // Each attribute is numbered once, by the first, however many follow.
+    #[validate(signal: "U32AsEnum")]
+    #[validate(method: "RequestName", direction: out)]
+    #[validate(method: "RequestName", direction: out, argument: 0)]
+    #[validate(signal: "U32AsEnum", argument: 0)]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct Quince(u32);
+
+    test_Quince_type_signature();
+    test_Quince_type_signature_1();
+    test_Quince_type_signature_2();
+    test_Quince_type_signature_3();
+}
+
+#[test]
+fn test_validate_macro_stacked_by_path() {
+    #[validate(signal: "U32AsEnum")]
+    #[zbus_lockstep_macros::validate(method: "RequestName", direction: out)]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct Grape(u32);
+
+    test_Grape_type_signature();
+    test_Grape_type_signature_1();
+}
+
+#[test]
+fn test_validate_macro_generic_instances() {
+    #[validate(signal: "RemoveNode", as: ["Event<String>", "Event<&str>"])]
+    #[derive(Debug, Type)]
+    struct Event<T: Type> {
+        _name: T,
+        _path: OwnedObjectPath,
+    }
+
+    test_Event_type_signature();
+}
+
+#[test]
+fn test_validate_macro_lifetimes_are_static() {
+    #[validate(signal: "Rename")]
+    #[derive(Debug, Type)]
+    struct Rename<'a> {
+        _old_name: &'a str,
+        _new_name: &'a str,
+    }
+
+    test_Rename_type_signature();
+}
+
+#[test]
+#[should_panic(
+    expected = "RemoveNode as `Event<u32>`: Signatures differ, expected `(so)`, found `(uo)`"
+)]
+fn test_validate_macro_reports_mismatching_instance() {
+    #[validate(signal: "RemoveNode", as: ["Event<String>", "Event<u32>"])]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct Event<T: Type> {
+        name: T,
+        path: OwnedObjectPath,
+    }
+
+    test_Event_type_signature();
+}
+
+#[test]
+fn test_validate_macro_naming() {
+    #[validate(naming: exact)]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct Changed(String);
+
+    #[validate(naming: strip_suffix)]
+    #[derive(Debug, Type)]
+    struct TextChangedEvent {
+        _text: String,
+        _offset: i32,
+    }
+
+    #[validate(naming: longest)]
+    #[derive(Debug, Type)]
+    struct TextChangedSignal {
+        _text: String,
+        _offset: i32,
+    }
+
+    #[validate(naming: snake_case)]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct ObjectAddedEvent(OwnedObjectPath);
+
+    #[validate(naming: strip_suffix, suffixes: ["Notification"])]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct ChangedNotification(String);
+
+    test_Changed_type_signature();
+    test_TextChangedEvent_type_signature();
+    test_TextChangedSignal_type_signature();
+    test_ObjectAddedEvent_type_signature();
+    test_ChangedNotification_type_signature();
+}
+
+#[test]
+fn test_validate_macro_finds_nested_definitions() {
+    #[validate(exclude: "**/draft/**")]
+    #[derive(Debug, Type)]
+    struct MovedSignal {
+        _x: i32,
+        _y: i32,
+    }
+
+    #[validate(include: "nested/draft/*.xml")]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct MovedDraft(String);
+
+    test_MovedSignal_type_signature();
+    test_MovedDraft_type_signature();
+}
+
+#[test]
+fn test_validate_macro_searches_several_roots() {
+    // The first root defines `Moved` once, the second defines it in another interface as well.
+    #[validate(xml: "xml/nested/draft", xml: "xml", signal: "Moved", interface: "org.example.NestedDraft")]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct DraftMoved(String);
+
+    test_DraftMoved_type_signature();
+}
+
+#[test]
+#[should_panic(expected = "defined in \"test_draft_file.xml\" of \"")]
+fn test_validate_macro_reports_source_of_definition() {
+    #[validate(xml: "xml/nested/draft", xml: "xml", signal: "Moved", interface: "org.example.NestedDraft")]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct DraftMoved(u32);
+
+    test_DraftMoved_type_signature();
+}
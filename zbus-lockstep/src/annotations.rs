@@ -0,0 +1,135 @@
+//! Annotations of interfaces, members, arguments and properties.
+
+/// The annotations of an interface, a member, an argument or a property.
+///
+/// Well-known annotations have typed accessors. Any annotation, including custom ones, can be
+/// read by name with [`Annotations::get`] or iterated over as name/value pairs.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::{MsgType, get_member_annotations};
+///
+/// let xml = r#"
+/// <node>
+///   <interface name="org.example.Node">
+///     <method name="Poke">
+///       <annotation name="org.freedesktop.DBus.Method.NoReply" value="true"/>
+///       <annotation name="org.example.Since" value="1.2"/>
+///     </method>
+///   </interface>
+/// </node>
+/// "#;
+///
+/// let annotations =
+///     get_member_annotations(xml.as_bytes(), "org.example.Node", MsgType::Method, "Poke")
+///         .unwrap();
+/// assert!(annotations.no_reply());
+/// assert!(!annotations.deprecated());
+/// assert_eq!(annotations.get("org.example.Since"), Some("1.2"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Annotations {
+    // Name/value pairs, in document order.
+    entries: Vec<(String, String)>,
+}
+
+impl Annotations {
+    /// `org.freedesktop.DBus.Deprecated`
+    pub const DEPRECATED: &'static str = "org.freedesktop.DBus.Deprecated";
+
+    /// `org.freedesktop.DBus.Method.NoReply`
+    pub const NO_REPLY: &'static str = "org.freedesktop.DBus.Method.NoReply";
+
+    /// `org.freedesktop.DBus.Property.EmitsChangedSignal`
+    pub const EMITS_CHANGED_SIGNAL: &'static str =
+        "org.freedesktop.DBus.Property.EmitsChangedSignal";
+
+    pub(crate) fn from_xml(annotations: &[zbus_xml::Annotation]) -> Self {
+        Annotations {
+            entries: annotations
+                .iter()
+                .map(|a| (a.name().to_owned(), a.value().to_owned()))
+                .collect(),
+        }
+    }
+
+    /// The value of the annotation called `name`, if present.
+    ///
+    /// Should an annotation occur more than once, the first value is returned.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// All annotations as name/value pairs, in document order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    /// The number of annotations.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether there are no annotations.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Whether `org.freedesktop.DBus.Deprecated` is `true`.
+    #[must_use]
+    pub fn deprecated(&self) -> bool {
+        self.get(Self::DEPRECATED) == Some("true")
+    }
+
+    /// Whether `org.freedesktop.DBus.Method.NoReply` is `true`.
+    #[must_use]
+    pub fn no_reply(&self) -> bool {
+        self.get(Self::NO_REPLY) == Some("true")
+    }
+
+    /// The value of `org.freedesktop.DBus.Property.EmitsChangedSignal`.
+    ///
+    /// `None` if the annotation is absent or has an unknown value. Note that a property without
+    /// the annotation inherits it from its interface, and defaults to
+    /// [`EmitsChangedSignal::True`] if neither has it.
+    #[must_use]
+    pub fn emits_changed_signal(&self) -> Option<EmitsChangedSignal> {
+        EmitsChangedSignal::from_value(self.get(Self::EMITS_CHANGED_SIGNAL)?)
+    }
+}
+
+/// Whether and how a property's changes are signalled with `PropertiesChanged`.
+///
+/// The values of the `org.freedesktop.DBus.Property.EmitsChangedSignal` annotation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EmitsChangedSignal {
+    /// The signal is emitted with the new value.
+    True,
+    /// The signal is emitted without the new value.
+    Invalidates,
+    /// The property never changes, no signal is emitted.
+    Const,
+    /// The signal is not emitted.
+    False,
+}
+
+impl EmitsChangedSignal {
+    fn from_value(value: &str) -> Option<Self> {
+        match value {
+            "true" => Some(EmitsChangedSignal::True),
+            "invalidates" => Some(EmitsChangedSignal::Invalidates),
+            "const" => Some(EmitsChangedSignal::Const),
+            "false" => Some(EmitsChangedSignal::False),
+            _ => None,
+        }
+    }
+}
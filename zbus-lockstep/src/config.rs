@@ -0,0 +1,207 @@
+//! Crate-wide configuration from `lockstep.toml` or `[package.metadata.lockstep]`.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use zbus_lockstep_common::config::{Config, Policy, Shape};
+
+use crate::{BodyShape, LockstepError, Result, SignaturePolicy};
+
+/// Settings shared by all lookups and `validate` attributes of a crate.
+///
+/// The configuration is read from `lockstep.toml` next to the crate's `Cargo.toml`, or else from
+/// the `[package.metadata.lockstep]` table of `Cargo.toml`. All keys are optional:
+///
+/// ```toml
+/// [package.metadata.lockstep]
+/// # The XML definitions directories, relative to the crate root, searched in order.
+/// xml = ["xml", "vendor/portal"]
+/// # The interface of members looked up without one.
+/// interface = "org.example.Node"
+/// # How `validate` infers member names, and the suffixes some strategies strip.
+/// naming = "strip_suffix"
+/// suffixes = ["Event", "Signal"]
+/// # How arguments are combined: `flattened`, `struct` or `auto`.
+/// shape = "auto"
+/// # The differences to accept: `exact`, `allow_sign_difference` or `allow_variant_widening`.
+/// policy = ["allow_sign_difference"]
+/// # Which XML files are read, see `XmlFilter`.
+/// include = ["org/**"]
+/// exclude = ["**/draft/**"]
+/// ```
+///
+/// In `lockstep.toml`, the same keys are at the top level. `naming` and `suffixes` only concern the
+/// `validate` attribute; they are checked here, but not kept. `zbus-lockstep-macros` reads the
+/// configuration with the same code, so both accept and reject the same configurations.
+///
+/// The `LOCKSTEP_*` environment variables take precedence over the arguments given to a lookup or
+/// an attribute, which take precedence over the configuration.
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct LockstepConfig {
+    /// The XML definitions directories, resolved against the crate root.
+    pub xml: Vec<PathBuf>,
+
+    /// The interface of members looked up without one.
+    pub interface: Option<String>,
+
+    /// How arguments are combined into a signature.
+    pub shape: Option<BodyShape>,
+
+    /// The differences between signatures to accept, see [`LockstepConfig::default_policy`].
+    pub policy: Option<SignaturePolicy>,
+
+    /// The patterns of the XML files to read.
+    pub include: Vec<String>,
+
+    /// The patterns of the XML files to skip.
+    pub exclude: Vec<String>,
+}
+
+impl LockstepConfig {
+    /// Read the configuration of the crate in `manifest_dir`.
+    ///
+    /// Returns `None` if the crate has neither a `lockstep.toml` nor a
+    /// `[package.metadata.lockstep]` table, and [`LockstepError::Config`] if the configuration is
+    /// malformed.
+    pub fn load(manifest_dir: impl AsRef<Path>) -> Result<Option<Self>> {
+        Ok(Config::load(manifest_dir)?.map(LockstepConfig::from))
+    }
+
+    /// The configuration of the crate being built or tested, found through `CARGO_MANIFEST_DIR`.
+    ///
+    /// It is read once and shared by all callers in the process.
+    pub fn shared() -> std::result::Result<Option<&'static Self>, &'static LockstepError> {
+        static SHARED: OnceLock<Result<Option<LockstepConfig>>> = OnceLock::new();
+
+        SHARED
+            .get_or_init(|| match Config::shared() {
+                Ok(config) => Ok(config.cloned().map(LockstepConfig::from)),
+                Err(err) => Err(err.clone().into()),
+            })
+            .as_ref()
+            .map(Option::as_ref)
+    }
+
+    /// The configured interface, used by the declarative macros when none is given.
+    ///
+    /// A malformed configuration counts as none; it is reported by
+    /// [`resolve_xml_path`](crate::resolve_xml_path).
+    #[must_use]
+    pub fn default_interface() -> Option<&'static str> {
+        Self::shared()
+            .ok()
+            .flatten()
+            .and_then(|config| config.interface.as_deref())
+    }
+
+    /// The configured shape, or [`BodyShape::Auto`], used by the declarative macros when none is
+    /// given.
+    #[must_use]
+    pub fn default_shape() -> BodyShape {
+        Self::shared()
+            .ok()
+            .flatten()
+            .and_then(|config| config.shape)
+            .unwrap_or_default()
+    }
+
+    /// The configured policy, or [`SignaturePolicy::Exact`], used by
+    /// [`assert_signature_eq!`](crate::assert_signature_eq) when none is given.
+    #[must_use]
+    pub fn default_policy() -> SignaturePolicy {
+        Self::shared()
+            .ok()
+            .flatten()
+            .and_then(|config| config.policy.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl From<Config> for LockstepConfig {
+    fn from(config: Config) -> Self {
+        let policy = config.policy.map(|policies| {
+            let mut policies: Vec<SignaturePolicy> = policies
+                .into_iter()
+                .map(|policy| match policy {
+                    Policy::Exact => SignaturePolicy::Exact,
+                    Policy::AllowSignDifference => SignaturePolicy::AllowSignDifference,
+                    Policy::AllowVariantWidening => SignaturePolicy::AllowVariantWidening,
+                })
+                .collect();
+            match policies.len() {
+                1 => policies.remove(0),
+                _ => SignaturePolicy::Any(policies),
+            }
+        });
+
+        LockstepConfig {
+            xml: config.xml,
+            interface: config.interface,
+            shape: config.shape.map(|shape| match shape {
+                Shape::Flattened => BodyShape::Flattened,
+                Shape::Struct => BodyShape::Struct,
+                Shape::Auto => BodyShape::Auto,
+            }),
+            policy,
+            include: config.include.unwrap_or_default(),
+            exclude: config.exclude.unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_config_from_metadata_table() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            r#"
+[package]
+name = "user"
+
+[package.metadata.lockstep]
+xml = ["xml", "vendor/portal"]
+interface = "org.example.Node"
+shape = "struct"
+policy = ["allow_sign_difference", "allow_variant_widening"]
+exclude = "**/draft/**"
+"#,
+        )
+        .unwrap();
+
+        let config = LockstepConfig::load(dir.path()).unwrap().unwrap();
+        assert_eq!(
+            config.xml,
+            [dir.path().join("xml"), dir.path().join("vendor/portal")]
+        );
+        assert_eq!(config.interface.as_deref(), Some("org.example.Node"));
+        assert_eq!(config.shape, Some(BodyShape::Struct));
+        assert!(
+            config
+                .policy
+                .unwrap()
+                .accepts(&zvariant::Signature::I32, &zvariant::Signature::U32)
+        );
+        assert_eq!(config.exclude, ["**/draft/**"]);
+        assert!(config.include.is_empty());
+    }
+
+    #[test]
+    fn test_malformed_config() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("lockstep.toml"), "naming = \"fuzzy\"\n").unwrap();
+
+        let err = LockstepConfig::load(dir.path()).unwrap_err();
+        assert!(matches!(err, LockstepError::Config { .. }));
+        assert!(
+            err.to_string().contains("unexpected naming `fuzzy`"),
+            "{err}"
+        );
+    }
+}
@@ -0,0 +1,431 @@
+//! Conformance of a live zbus interface implementation to its XML definition.
+
+use std::fmt;
+
+use zbus::object_server::Interface;
+use zbus_xml::{Arg, ArgDirection, Node, PropertyAccess};
+use zvariant::Signature;
+
+use crate::{
+    ArgSelector, BodyShape, LockstepError::XmlParse, MsgType, ProtocolIndex, Result,
+    error::access_str, property_of, selector::method_arg_direction, signature_of,
+};
+
+/// Compare the introspection data of a zbus interface implementation with its definition in
+/// `index`, member by member.
+///
+/// zbus introspects the implementation in-process, so the comparison covers whatever signatures
+/// the implementation ends up with, including those of generic or macro-generated code that the
+/// attribute macros cannot see.
+///
+/// Methods and signals are compared by the signatures of their arguments, properties by their
+/// type and access. Argument names are not compared, as zbus names arguments after the Rust
+/// parameters.
+///
+/// Returns [`LockstepError::InterfaceNotFound`](crate::LockstepError::InterfaceNotFound) if
+/// `index` does not define the interface.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus::interface;
+/// use zbus_lockstep::{ProtocolIndex, check_interface};
+///
+/// struct Greeter;
+///
+/// #[interface(name = "org.example.Greeter")]
+/// impl Greeter {
+///     fn greet(&self, name: &str) -> String {
+///         format!("Hello {name}!")
+///     }
+///
+///     #[zbus(property)]
+///     fn count(&self) -> i32 {
+///         0
+///     }
+/// }
+///
+/// let index = ProtocolIndex::from_strs([r#"
+/// <node>
+///   <interface name="org.example.Greeter">
+///     <method name="Greet">
+///       <arg name="name" type="s" direction="in"/>
+///       <arg name="greeting" type="s" direction="out"/>
+///     </method>
+///     <method name="Wave"/>
+///     <property name="Count" type="u" access="read"/>
+///   </interface>
+/// </node>
+/// "#])
+/// .unwrap();
+///
+/// let report = check_interface(&Greeter, &index).unwrap();
+/// assert!(!report.is_conformant());
+/// assert_eq!(report.missing, [(zbus_lockstep::MsgType::Method, "Wave".to_string())]);
+/// assert_eq!(report.mismatched[0].name, "Count");
+/// ```
+pub fn check_interface<I: Interface>(
+    interface: &I,
+    index: &ProtocolIndex,
+) -> Result<ConformanceReport> {
+    let interface_name = I::name().to_string();
+    let expected = index.interface(&interface_name)?;
+
+    let mut xml = String::from("<node>\n");
+    interface.introspect_to_writer(&mut xml, 2);
+    xml.push_str("</node>\n");
+    let node =
+        Node::from_reader(xml.as_bytes()).map_err(|source| XmlParse { path: None, source })?;
+    let found = node
+        .interfaces()
+        .iter()
+        .find(|found| found.name().as_str() == interface_name)
+        .expect("introspection data should contain the interface itself");
+
+    let mut report = ConformanceReport {
+        interface: interface_name.clone(),
+        missing: Vec::new(),
+        extra: Vec::new(),
+        mismatched: Vec::new(),
+    };
+    let name = interface_name.as_str();
+
+    for method in expected.methods() {
+        let member = &method.name().to_string();
+        let Some(found_method) = found.methods().iter().find(|m| m.name() == method.name()) else {
+            report.missing.push((MsgType::Method, member.to_owned()));
+            continue;
+        };
+
+        for direction in [ArgDirection::In, ArgDirection::Out] {
+            let expected = args_signature(method.args(), Some(direction), name, member)?;
+            let found = args_signature(found_method.args(), Some(direction), name, member)?;
+            if expected != found {
+                report.mismatched.push(MemberMismatch {
+                    kind: MsgType::Method,
+                    name: member.to_owned(),
+                    difference: Difference::Signature {
+                        direction: Some(direction),
+                        expected,
+                        found,
+                    },
+                });
+            }
+        }
+    }
+
+    for signal in expected.signals() {
+        let member = &signal.name().to_string();
+        let Some(found_signal) = found.signals().iter().find(|s| s.name() == signal.name()) else {
+            report.missing.push((MsgType::Signal, member.to_owned()));
+            continue;
+        };
+
+        let expected = args_signature(signal.args(), None, name, member)?;
+        let found = args_signature(found_signal.args(), None, name, member)?;
+        if expected != found {
+            report.mismatched.push(MemberMismatch {
+                kind: MsgType::Signal,
+                name: member.to_owned(),
+                difference: Difference::Signature {
+                    direction: None,
+                    expected,
+                    found,
+                },
+            });
+        }
+    }
+
+    for property in expected.properties() {
+        let member = &property.name().to_string();
+        if !found
+            .properties()
+            .iter()
+            .any(|p| p.name() == property.name())
+        {
+            report.missing.push((MsgType::Property, member.to_owned()));
+            continue;
+        }
+
+        let expected = property_of(expected, member)?;
+        let found = property_of(found, member)?;
+        if expected.signature != found.signature {
+            report.mismatched.push(MemberMismatch {
+                kind: MsgType::Property,
+                name: member.to_owned(),
+                difference: Difference::Signature {
+                    direction: None,
+                    expected: expected.signature,
+                    found: found.signature,
+                },
+            });
+        }
+        if expected.access != found.access {
+            report.mismatched.push(MemberMismatch {
+                kind: MsgType::Property,
+                name: member.to_owned(),
+                difference: Difference::Access {
+                    expected: expected.access,
+                    found: found.access,
+                },
+            });
+        }
+    }
+
+    let extra_methods = found
+        .methods()
+        .iter()
+        .filter(|m| !expected.methods().iter().any(|e| e.name() == m.name()))
+        .map(|m| (MsgType::Method, m.name().to_string()));
+    let extra_signals = found
+        .signals()
+        .iter()
+        .filter(|s| !expected.signals().iter().any(|e| e.name() == s.name()))
+        .map(|s| (MsgType::Signal, s.name().to_string()));
+    let extra_properties = found
+        .properties()
+        .iter()
+        .filter(|p| !expected.properties().iter().any(|e| e.name() == p.name()))
+        .map(|p| (MsgType::Property, p.name().to_string()));
+    report.extra = extra_methods
+        .chain(extra_signals)
+        .chain(extra_properties)
+        .collect();
+
+    Ok(report)
+}
+
+/// The outcome of [`check_interface`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConformanceReport {
+    /// The name of the interface that was checked.
+    pub interface: String,
+
+    /// Members defined in the XML that the implementation does not provide.
+    pub missing: Vec<(MsgType, String)>,
+
+    /// Members the implementation provides that the XML does not define.
+    pub extra: Vec<(MsgType, String)>,
+
+    /// Members that both define, but differently.
+    pub mismatched: Vec<MemberMismatch>,
+}
+
+impl ConformanceReport {
+    /// Whether the implementation matches the XML exactly.
+    #[must_use]
+    pub fn is_conformant(&self) -> bool {
+        self.missing.is_empty() && self.extra.is_empty() && self.mismatched.is_empty()
+    }
+}
+
+impl fmt::Display for ConformanceReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_conformant() {
+            return write!(f, "`{}` conforms to its definition.", self.interface);
+        }
+
+        write!(
+            f,
+            "`{}` does not conform to its definition:",
+            self.interface
+        )?;
+        for (kind, name) in &self.missing {
+            write!(f, "\n  missing {} `{name}`", kind_str(*kind))?;
+        }
+        for (kind, name) in &self.extra {
+            write!(f, "\n  extra {} `{name}`", kind_str(*kind))?;
+        }
+        for mismatch in &self.mismatched {
+            write!(f, "\n  {mismatch}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A member that the implementation and the XML define differently.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemberMismatch {
+    /// Whether the member is a method, signal or property.
+    pub kind: MsgType,
+
+    /// The name of the member.
+    pub name: String,
+
+    /// How the definitions differ.
+    pub difference: Difference,
+}
+
+impl fmt::Display for MemberMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} `{}`: ", kind_str(self.kind), self.name)?;
+
+        match &self.difference {
+            Difference::Signature {
+                direction,
+                expected,
+                found,
+            } => {
+                match direction {
+                    Some(ArgDirection::In) => write!(f, "arguments ")?,
+                    Some(ArgDirection::Out) => write!(f, "reply ")?,
+                    None => {}
+                }
+                write!(f, "expected `{expected}`, found `{found}`")
+            }
+            Difference::Access { expected, found } => {
+                write!(
+                    f,
+                    "expected access `{}`, found `{}`",
+                    access_str(*expected),
+                    access_str(*found)
+                )
+            }
+        }
+    }
+}
+
+/// How a member's definitions differ.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Difference {
+    /// The signatures differ: of a method's arguments or reply, as given by `direction`, of a
+    /// signal's arguments, or of a property's type.
+    Signature {
+        direction: Option<ArgDirection>,
+        expected: Signature,
+        found: Signature,
+    },
+    /// A property has another access mode.
+    Access {
+        expected: PropertyAccess,
+        found: PropertyAccess,
+    },
+}
+
+/// The signature of `args`, or of those in `direction` only.
+fn args_signature(
+    args: &[Arg],
+    direction: Option<ArgDirection>,
+    interface_name: &str,
+    member_name: &str,
+) -> Result<Signature> {
+    let selected: Vec<&Arg> = args
+        .iter()
+        .filter(|arg| direction.is_none_or(|d| method_arg_direction(arg) == d))
+        .collect();
+
+    signature_of(
+        &selected,
+        interface_name,
+        member_name,
+        &ArgSelector::All,
+        BodyShape::Flattened,
+    )
+}
+
+fn kind_str(kind: MsgType) -> &'static str {
+    match kind {
+        MsgType::Method => "method",
+        MsgType::Signal => "signal",
+        MsgType::Property => "property",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use zbus::{interface, object_server::SignalEmitter};
+
+    use super::*;
+
+    const XML: &str = r#"
+<node>
+  <interface name="org.example.Node">
+    <signal name="Rename">
+      <arg name="oldName" type="s"/>
+      <arg name="newName" type="s"/>
+    </signal>
+    <method name="RequestName">
+      <arg direction="in" name="apple" type="s"/>
+      <arg direction="in" name="orange" type="u"/>
+      <arg direction="out" name="grape" type="u"/>
+    </method>
+    <property name="Features" type="as" access="read"/>
+  </interface>
+</node>
+"#;
+
+    struct Node;
+
+    #[interface(name = "org.example.Node")]
+    impl Node {
+        fn request_name(&self, apple: &str, orange: u32) -> u32 {
+            apple.len() as u32 + orange
+        }
+
+        #[zbus(property)]
+        fn features(&self) -> Vec<String> {
+            Vec::new()
+        }
+
+        #[zbus(signal)]
+        async fn rename(
+            emitter: &SignalEmitter<'_>,
+            old_name: &str,
+            new_name: &str,
+        ) -> zbus::Result<()>;
+    }
+
+    struct DriftedNode;
+
+    #[interface(name = "org.example.Node")]
+    impl DriftedNode {
+        fn request_name(&self, apple: &str, orange: i32) -> u32 {
+            apple.len() as u32 + orange.unsigned_abs()
+        }
+
+        #[zbus(property)]
+        fn features(&self) -> Vec<String> {
+            Vec::new()
+        }
+
+        #[zbus(property)]
+        fn set_features(&mut self, _features: Vec<String>) {}
+
+        fn ping(&self) {}
+    }
+
+    #[test]
+    fn test_conforming_interface() {
+        let index = ProtocolIndex::from_strs([XML]).unwrap();
+        let report = check_interface(&Node, &index).unwrap();
+
+        assert!(report.is_conformant(), "{report}");
+    }
+
+    #[test]
+    fn test_drifted_interface_is_reported() {
+        let index = ProtocolIndex::from_strs([XML]).unwrap();
+        let report = check_interface(&DriftedNode, &index).unwrap();
+
+        assert_eq!(
+            report.to_string(),
+            "`org.example.Node` does not conform to its definition:\n  \
+             missing signal `Rename`\n  \
+             extra method `Ping`\n  \
+             method `RequestName`: arguments expected `(su)`, found `(si)`\n  \
+             property `Features`: expected access `read`, found `readwrite`"
+        );
+    }
+
+    #[test]
+    fn test_interface_missing_from_index() {
+        let index = ProtocolIndex::from_strs(["<node/>"]).unwrap();
+        let err = check_interface(&Node, &index).unwrap_err();
+
+        assert!(matches!(
+            err,
+            crate::LockstepError::InterfaceNotFound { .. }
+        ));
+    }
+}
@@ -0,0 +1,434 @@
+//! A structural comparison of two signatures.
+
+use std::fmt;
+
+use zvariant::Signature;
+
+use crate::SignaturePolicy;
+
+/// The differences between an expected and a found signature.
+///
+/// Both signatures are walked structurally: structure fields by position, array elements and
+/// dictionary keys and values. Each place where they diverge is reported as a [`Mismatch`] with
+/// the path that leads to it. The fields of the outermost structure can be given names, such as
+/// the field names of the Rust struct whose signature is compared.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::str::FromStr;
+/// use zbus_lockstep::SignatureDiff;
+/// use zvariant::Signature;
+///
+/// let expected = Signature::from_str("(sii)").unwrap();
+/// let found = Signature::from_str("(siu)").unwrap();
+///
+/// let diff = SignatureDiff::new(&expected, &found, &["name", "index", "child_count"]);
+/// assert_eq!(diff.mismatches().len(), 1);
+/// assert_eq!(
+///     diff.mismatches()[0].to_string(),
+///     "field 2 (`child_count`): expected `i`, found `u`"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignatureDiff {
+    expected: Signature,
+    found: Signature,
+    mismatches: Vec<Mismatch>,
+    relaxations: Vec<Relaxation>,
+}
+
+/// A place where two signatures diverge.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch {
+    /// The way from the outermost signature to the divergence, empty if the outermost signatures
+    /// themselves differ.
+    pub path: Vec<PathSegment>,
+
+    /// The expected signature at `path`, `None` if only the found signature has a field here.
+    pub expected: Option<Signature>,
+
+    /// The found signature at `path`, `None` if only the expected signature has a field here.
+    pub found: Option<Signature>,
+}
+
+/// A place where two signatures diverge, but a [`SignaturePolicy`] accepts it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Relaxation {
+    /// Where and how the signatures diverge.
+    pub mismatch: Mismatch,
+
+    /// The name of the relaxation that accepts it, e.g. `sign difference`.
+    pub name: String,
+}
+
+/// One step into a container signature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    /// A structure field, counting from zero, with its name if known.
+    Field { index: usize, name: Option<String> },
+    /// The element type of an array.
+    Element,
+    /// The key type of a dictionary.
+    Key,
+    /// The value type of a dictionary.
+    Value,
+}
+
+impl SignatureDiff {
+    /// Compare `found` to `expected`.
+    ///
+    /// `field_names` name the fields of the outermost structure, in order. Pass `&[]` if the
+    /// names are unknown.
+    #[must_use]
+    pub fn new(expected: &Signature, found: &Signature, field_names: &[&str]) -> Self {
+        Self::with_policy(expected, found, field_names, &SignaturePolicy::Exact)
+    }
+
+    /// Compare `found` to `expected`, accepting the differences that `policy` accepts.
+    ///
+    /// Accepted differences are not mismatches, but are reported as [`Relaxation`]s.
+    #[must_use]
+    pub fn with_policy(
+        expected: &Signature,
+        found: &Signature,
+        field_names: &[&str],
+        policy: &SignaturePolicy,
+    ) -> Self {
+        let mut walk = Walk {
+            field_names,
+            policy,
+            path: Vec::new(),
+            mismatches: Vec::new(),
+            relaxations: Vec::new(),
+        };
+        walk.walk(expected, found);
+
+        SignatureDiff {
+            expected: expected.clone(),
+            found: found.clone(),
+            mismatches: walk.mismatches,
+            relaxations: walk.relaxations,
+        }
+    }
+
+    /// Whether the signatures are equal, or differ only where the policy accepts it.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+
+    /// The places where the signatures diverge, in order.
+    #[must_use]
+    pub fn mismatches(&self) -> &[Mismatch] {
+        &self.mismatches
+    }
+
+    /// The places where the signatures diverge, but the policy accepts it, in order.
+    #[must_use]
+    pub fn relaxations(&self) -> &[Relaxation] {
+        &self.relaxations
+    }
+}
+
+// The state of walking two signatures side by side.
+struct Walk<'a> {
+    field_names: &'a [&'a str],
+    policy: &'a SignaturePolicy,
+    path: Vec<PathSegment>,
+    mismatches: Vec<Mismatch>,
+    relaxations: Vec<Relaxation>,
+}
+
+impl Walk<'_> {
+    fn walk(&mut self, expected: &Signature, found: &Signature) {
+        match (expected, found) {
+            (Signature::Structure(expected_fields), Signature::Structure(found_fields)) => {
+                let expected_fields: Vec<&Signature> = expected_fields.iter().collect();
+                let found_fields: Vec<&Signature> = found_fields.iter().collect();
+
+                for index in 0..expected_fields.len().max(found_fields.len()) {
+                    // Only the outermost structure's fields have known names.
+                    let name = self
+                        .field_names
+                        .get(index)
+                        .filter(|_| self.path.is_empty())
+                        .map(|name| (*name).to_owned());
+                    self.path.push(PathSegment::Field { index, name });
+
+                    match (expected_fields.get(index), found_fields.get(index)) {
+                        (Some(expected), Some(found)) => self.walk(expected, found),
+                        (expected, found) => self.mismatches.push(Mismatch {
+                            path: self.path.clone(),
+                            expected: expected.map(|s| (*s).clone()),
+                            found: found.map(|s| (*s).clone()),
+                        }),
+                    }
+
+                    self.path.pop();
+                }
+            }
+            (Signature::Array(expected), Signature::Array(found)) => {
+                self.path.push(PathSegment::Element);
+                self.walk(expected, found);
+                self.path.pop();
+            }
+            (
+                Signature::Dict {
+                    key: expected_key,
+                    value: expected_value,
+                },
+                Signature::Dict {
+                    key: found_key,
+                    value: found_value,
+                },
+            ) => {
+                self.path.push(PathSegment::Key);
+                self.walk(expected_key, found_key);
+                self.path.pop();
+
+                self.path.push(PathSegment::Value);
+                self.walk(expected_value, found_value);
+                self.path.pop();
+            }
+            (expected, found) if expected == found => {}
+            (expected, found) => {
+                let mismatch = Mismatch {
+                    path: self.path.clone(),
+                    expected: Some(expected.clone()),
+                    found: Some(found.clone()),
+                };
+                match self.policy.relaxation(expected, found) {
+                    Some(name) => self.relaxations.push(Relaxation {
+                        mismatch,
+                        name: name.to_owned(),
+                    }),
+                    None => self.mismatches.push(mismatch),
+                }
+            }
+        }
+    }
+}
+
+/// The signature as written, with `()` for the otherwise invisible unit signature.
+struct Shown<'a>(&'a Signature);
+
+impl fmt::Display for Shown<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Signature::Unit => write!(f, "`()`"),
+            signature => write!(f, "`{signature}`"),
+        }
+    }
+}
+
+impl fmt::Display for PathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathSegment::Field {
+                index,
+                name: Some(name),
+            } => write!(f, "field {index} (`{name}`)"),
+            PathSegment::Field { index, name: None } => write!(f, "field {index}"),
+            PathSegment::Element => write!(f, "element"),
+            PathSegment::Key => write!(f, "key"),
+            PathSegment::Value => write!(f, "value"),
+        }
+    }
+}
+
+/// The path to a place in a signature, `signature` for the outermost one.
+struct ShownPath<'a>(&'a [PathSegment]);
+
+impl fmt::Display for ShownPath<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            write!(f, "signature")?;
+        }
+        for (i, segment) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, " > ")?;
+            }
+            write!(f, "{segment}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", ShownPath(&self.path))?;
+
+        match (&self.expected, &self.found) {
+            (Some(expected), Some(found)) => {
+                write!(f, ": expected {}, found {}", Shown(expected), Shown(found))
+            }
+            (Some(expected), None) => write!(f, ": expected {}, found nothing", Shown(expected)),
+            (None, Some(found)) => write!(f, ": unexpected {}", Shown(found)),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for Relaxation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", ShownPath(&self.mismatch.path))?;
+
+        if let (Some(expected), Some(found)) = (&self.mismatch.expected, &self.mismatch.found) {
+            write!(
+                f,
+                ": {} accepted for {} ({})",
+                Shown(found),
+                Shown(expected),
+                self.name
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for SignatureDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.is_empty(), self.relaxations.is_empty()) {
+            (true, true) => return write!(f, "Signatures are equal: {}", Shown(&self.expected)),
+            (true, false) => write!(
+                f,
+                "Signatures are compatible, expected {}, found {}:",
+                Shown(&self.expected),
+                Shown(&self.found)
+            )?,
+            (false, _) => write!(
+                f,
+                "Signatures differ, expected {}, found {}:",
+                Shown(&self.expected),
+                Shown(&self.found)
+            )?,
+        }
+
+        for mismatch in &self.mismatches {
+            write!(f, "\n  {mismatch}")?;
+        }
+        for relaxation in &self.relaxations {
+            write!(f, "\n  {relaxation}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use zvariant::Signature;
+
+    use super::{PathSegment, SignatureDiff};
+    use crate::SignaturePolicy;
+
+    fn sig(s: &str) -> Signature {
+        Signature::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_equal_signatures_have_no_mismatches() {
+        let diff = SignatureDiff::new(&sig("((so)a{sv})"), &sig("((so)a{sv})"), &[]);
+        assert!(diff.is_empty());
+    }
+
+    #[test]
+    fn test_reports_named_fields_of_the_outermost_structure() {
+        let expected = sig("((so)(so)(so)iiassusau)");
+        let found = sig("((so)(so)(so)iuassusau)");
+        let names = [
+            "obj",
+            "application",
+            "parent",
+            "index_in_parent",
+            "child_count",
+        ];
+
+        let diff = SignatureDiff::new(&expected, &found, &names);
+        assert_eq!(diff.mismatches().len(), 1);
+        assert_eq!(
+            diff.mismatches()[0].to_string(),
+            "field 4 (`child_count`): expected `i`, found `u`"
+        );
+    }
+
+    #[test]
+    fn test_reports_paths_into_nested_containers() {
+        let diff = SignatureDiff::new(&sig("(a(so)a{sv})"), &sig("(a(ss)a{uv})"), &["items"]);
+        let paths: Vec<String> = diff.mismatches().iter().map(ToString::to_string).collect();
+        assert_eq!(
+            paths,
+            [
+                "field 0 (`items`) > element > field 1: expected `o`, found `s`",
+                "field 1 > key: expected `s`, found `u`",
+            ]
+        );
+        assert_eq!(
+            diff.mismatches()[1].path,
+            [
+                PathSegment::Field {
+                    index: 1,
+                    name: None
+                },
+                PathSegment::Key
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reports_missing_and_unexpected_fields() {
+        let diff = SignatureDiff::new(&sig("(su)"), &sig("(s)"), &["name", "id"]);
+        assert_eq!(
+            diff.mismatches()[0].to_string(),
+            "field 1 (`id`): expected `u`, found nothing"
+        );
+
+        let diff = SignatureDiff::new(&sig("(s)"), &sig("(su)"), &[]);
+        assert_eq!(diff.mismatches()[0].to_string(), "field 1: unexpected `u`");
+    }
+
+    #[test]
+    fn test_policy_turns_mismatches_into_relaxations() {
+        let policy = SignaturePolicy::AllowSignDifference.or(SignaturePolicy::AllowVariantWidening);
+        let diff = SignatureDiff::with_policy(
+            &sig("(sia{ss})"),
+            &sig("(sua{sv})"),
+            &["name", "role", "attributes"],
+            &policy,
+        );
+
+        assert!(diff.is_empty());
+        let relaxations: Vec<String> = diff.relaxations().iter().map(ToString::to_string).collect();
+        assert_eq!(
+            relaxations,
+            [
+                "field 1 (`role`): `u` accepted for `i` (sign difference)",
+                "field 2 (`attributes`) > value: `v` accepted for `s` (variant widening)",
+            ]
+        );
+
+        // Only the differences the policy accepts are relaxed.
+        let diff = SignatureDiff::with_policy(
+            &sig("(si)"),
+            &sig("(su)"),
+            &[],
+            &SignaturePolicy::AllowVariantWidening,
+        );
+        assert_eq!(diff.mismatches().len(), 1);
+        assert!(diff.relaxations().is_empty());
+    }
+
+    #[test]
+    fn test_reports_differing_outermost_signatures() {
+        let diff = SignatureDiff::new(&sig("u"), &Signature::Unit, &[]);
+        assert_eq!(
+            diff.to_string(),
+            "Signatures differ, expected `u`, found `()`:\n  signature: expected `u`, found `()`"
+        );
+    }
+}
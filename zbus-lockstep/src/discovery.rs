@@ -0,0 +1,41 @@
+//! Discovery of the XML files in a definitions directory.
+//!
+//! The filter and the walk are shared with `zbus-lockstep-macros`, through `zbus-lockstep-common`.
+
+use std::path::{Path, PathBuf};
+
+pub use zbus_lockstep_common::discovery::XmlFilter;
+
+use crate::Result;
+
+/// The XML files in `dir` and its subdirectories that `filter` accepts.
+///
+/// The files are sorted by path, so that they are always read in the same order. Symbolic links
+/// to directories are not followed.
+///
+/// A directory that cannot be read is reported as [`LockstepError::XmlDir`](crate::LockstepError::XmlDir).
+pub fn discover_xml_files(dir: impl AsRef<Path>, filter: &XmlFilter) -> Result<Vec<PathBuf>> {
+    Ok(zbus_lockstep_common::discovery::discover_xml_files(
+        dir.as_ref(),
+        filter,
+    )?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::LockstepError;
+
+    #[test]
+    fn test_unreadable_directory_is_named() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("missing");
+
+        let err = discover_xml_files(&missing, &XmlFilter::new()).unwrap_err();
+        assert!(
+            matches!(&err, LockstepError::XmlDir { path, .. } if *path == missing),
+            "{err}"
+        );
+        assert!(err.to_string().contains("missing"), "{err}");
+    }
+}
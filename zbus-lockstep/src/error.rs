@@ -1,29 +1,371 @@
+use std::path::{Path, PathBuf};
+
+use zbus_lockstep_common::{
+    config::ConfigError,
+    discovery::DirError,
+    roots::RootError,
+    suggest::{did_you_mean, format_suggestions},
+};
+
+/// The not-found variants carry the names that were available where the lookup took place, which
+/// are used to suggest close matches.
+///
+/// When the lookup took place in a [`ProtocolIndex`](crate::ProtocolIndex) built from
+/// directories, the interface and not-found variants also name the roots that were searched.
 #[non_exhaustive]
 #[derive(Debug)]
 pub enum LockstepError {
-    ArgumentNotFound(String),
-    InterfaceNotFound(String),
-    MemberNotFound(String),
-    PropertyNotFound(String),
+    ArgumentNotFound {
+        name: String,
+        available: Vec<String>,
+    },
+    InterfaceNotFound {
+        name: String,
+        available: Vec<String>,
+        searched: Vec<PathBuf>,
+    },
+    MemberNotFound {
+        name: String,
+        available: Vec<String>,
+        searched: Vec<PathBuf>,
+    },
+    PropertyNotFound {
+        name: String,
+        available: Vec<String>,
+        searched: Vec<PathBuf>,
+    },
+    /// A method argument was selected by name, but it has the other direction.
+    ArgumentDirection {
+        name: String,
+        expected: zbus_xml::ArgDirection,
+        actual: zbus_xml::ArgDirection,
+    },
+    /// A property does not have the expected access mode.
+    PropertyAccessMismatch {
+        name: String,
+        expected: zbus_xml::PropertyAccess,
+        actual: zbus_xml::PropertyAccess,
+    },
+    /// An argument was selected by a position the member does not have.
+    ArgumentIndexOutOfRange {
+        interface: String,
+        member: String,
+        index: usize,
+        count: usize,
+    },
+    /// Arguments were selected by a range of positions that is reversed or reaches past the
+    /// member's arguments.
+    ArgumentRangeOutOfRange {
+        interface: String,
+        member: String,
+        range: std::ops::Range<usize>,
+        count: usize,
+    },
+    /// The XML could not be parsed as a `DBus` introspection document.
+    ///
+    /// `path` is set when the XML was read from a file.
+    XmlParse {
+        path: Option<PathBuf>,
+        source: zbus_xml::Error,
+    },
+    /// The type string found in the XML is not a valid signature.
+    InvalidSignature {
+        interface: String,
+        member: String,
+        arg: Option<String>,
+        raw: String,
+    },
+    /// Reading XML files or resolving the XML path failed.
+    Io(std::io::Error),
+    /// The XML definitions directory, or one of its subdirectories, at `path` could not be read.
+    XmlDir {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// The XML file at `path` could not be read.
+    XmlFile {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// The crate's lockstep configuration, read from `path`, is malformed.
+    Config { path: PathBuf, message: String },
+    /// More than one interface offers the requested member.
+    ///
+    /// `sources` holds the XML file of each of the `candidates`, if it was read from a file, and
+    /// `roots` the directory that file was found in, if it was found by searching a directory.
+    Ambiguous {
+        member: String,
+        candidates: Vec<String>,
+        sources: Vec<Option<PathBuf>>,
+        roots: Vec<Option<PathBuf>>,
+    },
+}
+
+impl std::error::Error for LockstepError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LockstepError::XmlParse { source, .. } => Some(source),
+            LockstepError::Io(source)
+            | LockstepError::XmlDir { source, .. }
+            | LockstepError::XmlFile { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl LockstepError {
+    /// Names close to the one that was not found, closest first.
+    ///
+    /// Empty for errors other than the not-found variants.
+    #[must_use]
+    pub fn suggestions(&self) -> Vec<&str> {
+        match self {
+            LockstepError::ArgumentNotFound { name, available }
+            | LockstepError::InterfaceNotFound {
+                name, available, ..
+            }
+            | LockstepError::MemberNotFound {
+                name, available, ..
+            }
+            | LockstepError::PropertyNotFound {
+                name, available, ..
+            } => did_you_mean(name, available.iter().map(String::as_str)),
+            _ => Vec::new(),
+        }
+    }
+
+    /// The error, with `roots` as the searched roots if it is a not-found error that has none yet.
+    pub(crate) fn searched_in(mut self, roots: &[PathBuf]) -> Self {
+        if let LockstepError::InterfaceNotFound { searched, .. }
+        | LockstepError::MemberNotFound { searched, .. }
+        | LockstepError::PropertyNotFound { searched, .. } = &mut self
+        {
+            if searched.is_empty() {
+                searched.extend_from_slice(roots);
+            }
+        }
+        self
+    }
 }
 
-impl std::error::Error for LockstepError {}
+impl From<std::io::Error> for LockstepError {
+    fn from(err: std::io::Error) -> Self {
+        LockstepError::Io(err)
+    }
+}
+
+impl From<ConfigError> for LockstepError {
+    fn from(err: ConfigError) -> Self {
+        LockstepError::Config {
+            path: err.path,
+            message: err.message,
+        }
+    }
+}
+
+impl From<DirError> for LockstepError {
+    fn from(err: DirError) -> Self {
+        LockstepError::XmlDir {
+            path: err.path,
+            source: err.source,
+        }
+    }
+}
+
+impl From<RootError> for LockstepError {
+    fn from(err: RootError) -> Self {
+        let kind = match &err {
+            RootError::Canonicalize { source, .. } => source.kind(),
+            _ => std::io::ErrorKind::NotFound,
+        };
+        LockstepError::Io(std::io::Error::new(kind, err.to_string()))
+    }
+}
 
 impl std::fmt::Display for LockstepError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            LockstepError::ArgumentNotFound(name) => {
-                write!(f, "Argument \"{name}\" not found.")
+            LockstepError::ArgumentNotFound { name, .. } => {
+                let suggestions = format_suggestions(&self.suggestions());
+                write!(f, "Argument \"{name}\" not found.{suggestions}")
+            }
+            LockstepError::InterfaceNotFound { name, searched, .. } => {
+                let suggestions = format_suggestions(&self.suggestions());
+                let searched = format_searched(searched);
+                write!(f, "Interface \"{name}\" not found{searched}.{suggestions}")
+            }
+            LockstepError::MemberNotFound { name, searched, .. } => {
+                let suggestions = format_suggestions(&self.suggestions());
+                let searched = format_searched(searched);
+                write!(f, "Member \"{name}\" not found{searched}.{suggestions}")
             }
-            LockstepError::InterfaceNotFound(name) => {
-                write!(f, "Interface \"{name}\" not found.")
+            LockstepError::PropertyNotFound { name, searched, .. } => {
+                let suggestions = format_suggestions(&self.suggestions());
+                let searched = format_searched(searched);
+                write!(f, "Property \"{name}\" not found{searched}.{suggestions}")
             }
-            LockstepError::MemberNotFound(name) => {
-                write!(f, "Member \"{name}\" not found.")
+            LockstepError::ArgumentDirection {
+                name,
+                expected,
+                actual,
+            } => {
+                write!(
+                    f,
+                    "Argument \"{name}\" has direction \"{}\", expected \"{}\".",
+                    direction_str(*actual),
+                    direction_str(*expected)
+                )
             }
-            LockstepError::PropertyNotFound(name) => {
-                write!(f, "Property \"{name}\" not found.")
+            LockstepError::PropertyAccessMismatch {
+                name,
+                expected,
+                actual,
+            } => {
+                write!(
+                    f,
+                    "Property \"{name}\" has access \"{}\", expected \"{}\".",
+                    access_str(*actual),
+                    access_str(*expected)
+                )
+            }
+            LockstepError::ArgumentIndexOutOfRange {
+                interface,
+                member,
+                index,
+                count,
+            } => {
+                write!(
+                    f,
+                    "Argument #{index} of {interface}.{member} is out of range, the member has \
+                     {count} argument(s)."
+                )
+            }
+            LockstepError::ArgumentRangeOutOfRange {
+                interface,
+                member,
+                range,
+                count,
+            } => {
+                let (start, end) = (range.start, range.end);
+                if start > end {
+                    write!(
+                        f,
+                        "Arguments #{start}..#{end} of {interface}.{member} are a reversed range."
+                    )
+                } else {
+                    write!(
+                        f,
+                        "Arguments #{start}..#{end} of {interface}.{member} are out of range, the \
+                         member has {count} argument(s)."
+                    )
+                }
+            }
+            LockstepError::XmlParse { path, source } => match path {
+                Some(path) => write!(
+                    f,
+                    "Failed to parse XML file \"{}\": {source}",
+                    path.display()
+                ),
+                None => write!(f, "Failed to parse XML: {source}"),
+            },
+            LockstepError::InvalidSignature {
+                interface,
+                member,
+                arg,
+                raw,
+            } => {
+                write!(f, "Invalid signature \"{raw}\" in {interface}.{member}")?;
+                if let Some(arg) = arg {
+                    write!(f, ", argument \"{arg}\"")?;
+                }
+                write!(f, ".")
+            }
+            LockstepError::Io(err) => write!(f, "I/O error: {err}"),
+            LockstepError::XmlDir { path, source } => write!(
+                f,
+                "Failed to read XML directory \"{}\": {source}",
+                path.display()
+            ),
+            LockstepError::XmlFile { path, source } => write!(
+                f,
+                "Failed to read XML file \"{}\": {source}",
+                path.display()
+            ),
+            LockstepError::Config { path, message } => {
+                write!(
+                    f,
+                    "Invalid configuration in \"{}\": {message}",
+                    path.display()
+                )
+            }
+            LockstepError::Ambiguous {
+                member,
+                candidates,
+                sources,
+                roots,
+            } => {
+                let candidates = candidates
+                    .iter()
+                    .enumerate()
+                    .map(|(i, candidate)| {
+                        let source = sources.get(i).and_then(Option::as_deref);
+                        let root = roots.get(i).and_then(Option::as_deref);
+                        match source {
+                            Some(path) => {
+                                format!("{candidate} ({})", describe_source(path, root))
+                            }
+                            None => candidate.clone(),
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                write!(
+                    f,
+                    "Multiple interfaces offer member \"{member}\": {}. Please specify the interface name.",
+                    candidates.join(", ")
+                )
             }
         }
     }
 }
+
+/// The XML file at `path`, relative to the `root` it was found in, if any: `"file" of "root"`.
+pub(crate) fn describe_source(path: &Path, root: Option<&Path>) -> String {
+    match root {
+        Some(root) => format!(
+            "\"{}\" of \"{}\"",
+            path.strip_prefix(root).unwrap_or(path).display(),
+            root.display()
+        ),
+        None => format!("\"{}\"", path.display()),
+    }
+}
+
+/// ` in "a", "b"` for the `searched` roots, or nothing if there are none.
+fn format_searched(searched: &[PathBuf]) -> String {
+    if searched.is_empty() {
+        return String::new();
+    }
+
+    let roots = searched
+        .iter()
+        .map(|root| format!("\"{}\"", root.display()))
+        .collect::<Vec<_>>();
+    format!(" in {}", roots.join(", "))
+}
+
+/// The direction as it is written in XML.
+fn direction_str(direction: zbus_xml::ArgDirection) -> &'static str {
+    match direction {
+        zbus_xml::ArgDirection::In => "in",
+        zbus_xml::ArgDirection::Out => "out",
+    }
+}
+
+/// The access mode as it is written in XML.
+pub(crate) fn access_str(access: zbus_xml::PropertyAccess) -> &'static str {
+    match access {
+        zbus_xml::PropertyAccess::Read => "read",
+        zbus_xml::PropertyAccess::Write => "write",
+        zbus_xml::PropertyAccess::ReadWrite => "readwrite",
+    }
+}
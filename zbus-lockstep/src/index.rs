@@ -0,0 +1,553 @@
+//! An in-memory index of `DBus` XML definitions.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use zbus_xml::{ArgDirection, Interface, Node};
+use zvariant::Signature;
+
+use crate::{
+    Annotations, ArgSelector, BodyShape,
+    LockstepError::{
+        self, Ambiguous, InterfaceNotFound, MemberNotFound, PropertyNotFound, XmlFile, XmlParse,
+    },
+    MsgType, PropertyDescriptor, Result, XmlFilter, arg_annotations_of, discover_xml_files,
+    error::describe_source,
+    member_annotations_of, method_arg_names_of, method_args_type_of, method_return_type_of,
+    property_of, property_type_of, resolve_xml_path, signal_arg_names_of, signal_body_type_of,
+};
+
+/// An index over a set of `DBus` XML documents.
+///
+/// The XML is parsed once, when the index is built. Interfaces and their methods, signals and
+/// properties are indexed by name, so that the signature queries are answered from memory.
+///
+/// If an interface is defined more than once, the definition that was indexed first is used. An
+/// index can be built from several directories, its roots, which are searched in the order given.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::{BodyShape, MsgType, ProtocolIndex};
+///
+/// let index = ProtocolIndex::from_strs([r#"
+/// <node>
+///   <interface name="org.example.Node">
+///     <signal name="RemoveNode">
+///       <arg name="nodeRemoved" type="(so)"/>
+///     </signal>
+///   </interface>
+/// </node>
+/// "#])
+/// .unwrap();
+///
+/// let definition = index.locate(MsgType::Signal, "RemoveNode", None).unwrap();
+/// assert_eq!(definition.interface, "org.example.Node");
+///
+/// let signature = index
+///     .signal_body_type(definition.interface, "RemoveNode", None, BodyShape::Auto)
+///     .unwrap();
+/// assert_eq!(signature, "(so)");
+/// ```
+#[derive(Debug)]
+pub struct ProtocolIndex {
+    // The parsed documents, in the order they were indexed.
+    documents: Vec<Document>,
+
+    // Interface name to (document, interface) position.
+    interfaces: BTreeMap<String, (usize, usize)>,
+
+    // Member name to the names of the interfaces that offer it.
+    members: HashMap<(MsgType, String), Vec<String>>,
+
+    // The directories the index was built from, named in not-found errors.
+    roots: Vec<PathBuf>,
+}
+
+/// A parsed XML document.
+#[derive(Debug)]
+struct Document {
+    // The directory the file was found in, if it was found by searching a directory.
+    root: Option<PathBuf>,
+
+    // The file the document was read from, if any.
+    path: Option<PathBuf>,
+
+    node: Node<'static>,
+}
+
+/// Where a member is defined.
+///
+/// Displays as the interface name, followed by the file and root it came from, if known.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Definition<'a> {
+    /// The name of the interface that offers the member.
+    pub interface: &'a str,
+
+    /// The XML file the interface was read from, if any.
+    pub path: Option<&'a Path>,
+
+    /// The directory in which `path` was found, if the index was built from directories.
+    pub root: Option<&'a Path>,
+}
+
+impl fmt::Display for Definition<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.interface)?;
+        match self.path {
+            Some(path) => write!(f, " in {}", describe_source(path, self.root)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl ProtocolIndex {
+    /// Index all XML files in `dir` and its subdirectories.
+    ///
+    /// Files are indexed in path order. `LOCKSTEP_XML_INCLUDE` and `LOCKSTEP_XML_EXCLUDE` narrow
+    /// down the files that are read, see [`XmlFilter::from_env`].
+    pub fn from_dir(dir: impl AsRef<Path>) -> Result<Self> {
+        Self::from_dirs([dir])
+    }
+
+    /// Index the XML files in `dir` and its subdirectories that `filter` accepts.
+    ///
+    /// Files are indexed in path order.
+    pub fn from_dir_filtered(dir: impl AsRef<Path>, filter: &XmlFilter) -> Result<Self> {
+        Self::from_dirs_filtered([dir], filter)
+    }
+
+    /// Index all XML files in each of `dirs` and their subdirectories.
+    ///
+    /// The directories are indexed in the order given, the files of each in path order, so that an
+    /// interface defined in more than one directory is taken from the first.
+    /// `LOCKSTEP_XML_INCLUDE` and `LOCKSTEP_XML_EXCLUDE` narrow down the files that are read, see
+    /// [`XmlFilter::from_env`].
+    pub fn from_dirs(dirs: impl IntoIterator<Item = impl AsRef<Path>>) -> Result<Self> {
+        Self::from_dirs_filtered(dirs, &XmlFilter::from_env())
+    }
+
+    /// Index the XML files in each of `dirs` and their subdirectories that `filter` accepts.
+    ///
+    /// The directories are indexed in the order given, the files of each in path order.
+    pub fn from_dirs_filtered(
+        dirs: impl IntoIterator<Item = impl AsRef<Path>>,
+        filter: &XmlFilter,
+    ) -> Result<Self> {
+        let mut documents = Vec::new();
+        let mut roots = Vec::new();
+        for dir in dirs {
+            let dir = dir.as_ref();
+            for path in discover_xml_files(dir, filter)? {
+                let mut document = read_document(path)?;
+                document.root = Some(dir.to_owned());
+                documents.push(document);
+            }
+            roots.push(dir.to_owned());
+        }
+
+        let mut index = Self::from_documents(documents);
+        index.roots = roots;
+        Ok(index)
+    }
+
+    /// Index the given XML files, in the order given.
+    pub fn from_files(paths: impl IntoIterator<Item = impl AsRef<Path>>) -> Result<Self> {
+        let documents = paths
+            .into_iter()
+            .map(|path| read_document(path.as_ref().to_owned()))
+            .collect::<Result<_>>()?;
+
+        Ok(Self::from_documents(documents))
+    }
+
+    /// Index the given XML documents, in the order given.
+    pub fn from_strs(xml: impl IntoIterator<Item = impl AsRef<str>>) -> Result<Self> {
+        let mut documents = Vec::new();
+        for xml in xml {
+            let node = Node::from_reader(xml.as_ref().as_bytes())
+                .map_err(|source| XmlParse { path: None, source })?;
+            documents.push(Document {
+                root: None,
+                path: None,
+                node,
+            });
+        }
+
+        Ok(Self::from_documents(documents))
+    }
+
+    /// A lazily initialized index, shared by all callers in the process.
+    ///
+    /// The index is built on first use from the directories found by
+    /// [`resolve_xml_path(None)`](crate::resolve_xml_path). The declarative macros use this index,
+    /// so that the XML is parsed only once, however many lookups are made.
+    pub fn shared() -> std::result::Result<&'static Self, &'static LockstepError> {
+        static SHARED: OnceLock<Result<ProtocolIndex>> = OnceLock::new();
+
+        SHARED
+            .get_or_init(|| ProtocolIndex::from_dirs(resolve_xml_path(None)?))
+            .as_ref()
+    }
+
+    fn from_documents(documents: Vec<Document>) -> Self {
+        let mut interfaces = BTreeMap::new();
+        let mut members: HashMap<(MsgType, String), Vec<String>> = HashMap::new();
+
+        for (document_idx, document) in documents.iter().enumerate() {
+            for (interface_idx, interface) in document.node.interfaces().iter().enumerate() {
+                let name = interface.name().to_string();
+                if interfaces.contains_key(&name) {
+                    continue;
+                }
+
+                let member_names = interface
+                    .methods()
+                    .iter()
+                    .map(|m| (MsgType::Method, m.name().to_string()))
+                    .chain(
+                        interface
+                            .signals()
+                            .iter()
+                            .map(|s| (MsgType::Signal, s.name().to_string())),
+                    )
+                    .chain(
+                        interface
+                            .properties()
+                            .iter()
+                            .map(|p| (MsgType::Property, p.name().to_string())),
+                    );
+                for key in member_names {
+                    members.entry(key).or_default().push(name.clone());
+                }
+
+                interfaces.insert(name, (document_idx, interface_idx));
+            }
+        }
+
+        ProtocolIndex {
+            documents,
+            interfaces,
+            members,
+            roots: Vec::new(),
+        }
+    }
+
+    /// The directories the index was built from, in the order they are searched.
+    ///
+    /// Empty if the index was built from files or strings.
+    #[must_use]
+    pub fn roots(&self) -> &[PathBuf] {
+        &self.roots
+    }
+
+    /// The names of all indexed interfaces, in alphabetical order.
+    pub fn interface_names(&self) -> impl Iterator<Item = &str> {
+        self.interfaces.keys().map(String::as_str)
+    }
+
+    /// The XML file that defines `interface_name`, if it was read from a file.
+    #[must_use]
+    pub fn source_file(&self, interface_name: &str) -> Option<&Path> {
+        self.document(interface_name)?.path.as_deref()
+    }
+
+    /// The directory in which the XML file that defines `interface_name` was found, if the index
+    /// was built from directories.
+    #[must_use]
+    pub fn source_root(&self, interface_name: &str) -> Option<&Path> {
+        self.document(interface_name)?.root.as_deref()
+    }
+
+    /// Where `interface_name` is defined.
+    ///
+    /// Returns [`LockstepError::InterfaceNotFound`] if no indexed document defines it.
+    pub fn definition(&self, interface_name: &str) -> Result<Definition<'_>> {
+        let (name, _) = self
+            .interfaces
+            .get_key_value(interface_name)
+            .ok_or_else(|| InterfaceNotFound {
+                name: interface_name.to_owned(),
+                available: self.interface_names().map(ToOwned::to_owned).collect(),
+                searched: self.roots.clone(),
+            })?;
+
+        Ok(Definition {
+            interface: name,
+            path: self.source_file(name),
+            root: self.source_root(name),
+        })
+    }
+
+    /// Find the interface that offers `member`.
+    ///
+    /// If `interface` is provided, only that interface is considered. Otherwise the member must be
+    /// offered by exactly one interface, or [`LockstepError::Ambiguous`] is returned.
+    pub fn locate(
+        &self,
+        msg_type: MsgType,
+        member: &str,
+        interface: Option<&str>,
+    ) -> Result<Definition<'_>> {
+        let not_found = |available: Vec<String>| match msg_type {
+            MsgType::Property => PropertyNotFound {
+                name: member.to_owned(),
+                available,
+                searched: self.roots.clone(),
+            },
+            _ => MemberNotFound {
+                name: member.to_owned(),
+                available,
+                searched: self.roots.clone(),
+            },
+        };
+
+        let offered_by = self
+            .members
+            .get(&(msg_type, member.to_owned()))
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        let interface_name = if let Some(interface_name) = interface {
+            let (name, _) = self
+                .interfaces
+                .get_key_value(interface_name)
+                .ok_or_else(|| InterfaceNotFound {
+                    name: interface_name.to_owned(),
+                    available: self.interface_names().map(ToOwned::to_owned).collect(),
+                    searched: self.roots.clone(),
+                })?;
+            if !offered_by.contains(name) {
+                return Err(not_found(self.member_names(msg_type, Some(name))));
+            }
+            name
+        } else {
+            match offered_by {
+                [] => return Err(not_found(self.member_names(msg_type, None))),
+                [name] => name,
+                candidates => {
+                    return Err(Ambiguous {
+                        member: member.to_owned(),
+                        candidates: candidates.to_vec(),
+                        sources: candidates
+                            .iter()
+                            .map(|name| self.source_file(name).map(Path::to_owned))
+                            .collect(),
+                        roots: candidates
+                            .iter()
+                            .map(|name| self.source_root(name).map(Path::to_owned))
+                            .collect(),
+                    });
+                }
+            }
+        };
+
+        self.definition(interface_name)
+    }
+
+    /// Retrieve a signal's body type signature.
+    ///
+    /// See [`get_signal_body_type`](crate::get_signal_body_type).
+    pub fn signal_body_type(
+        &self,
+        interface_name: &str,
+        member_name: &str,
+        arg: impl Into<ArgSelector>,
+        shape: BodyShape,
+    ) -> Result<Signature> {
+        signal_body_type_of(
+            self.interface(interface_name)?,
+            member_name,
+            &arg.into(),
+            shape,
+        )
+        .map_err(|e| e.searched_in(&self.roots))
+    }
+
+    /// Retrieve the names of a signal's arguments.
+    ///
+    /// See [`get_signal_arg_names`](crate::get_signal_arg_names).
+    pub fn signal_arg_names(
+        &self,
+        interface_name: &str,
+        member_name: &str,
+        arg: impl Into<ArgSelector>,
+    ) -> Result<Vec<Option<String>>> {
+        signal_arg_names_of(self.interface(interface_name)?, member_name, &arg.into())
+            .map_err(|e| e.searched_in(&self.roots))
+    }
+
+    /// Retrieve the signature of a property's type.
+    ///
+    /// See [`get_property_type`](crate::get_property_type).
+    pub fn property_type(&self, interface_name: &str, property_name: &str) -> Result<Signature> {
+        property_type_of(self.interface(interface_name)?, property_name)
+            .map_err(|e| e.searched_in(&self.roots))
+    }
+
+    /// Retrieve the full description of a property.
+    ///
+    /// See [`get_property`](crate::get_property).
+    pub fn property(
+        &self,
+        interface_name: &str,
+        property_name: &str,
+    ) -> Result<PropertyDescriptor> {
+        property_of(self.interface(interface_name)?, property_name)
+            .map_err(|e| e.searched_in(&self.roots))
+    }
+
+    /// Retrieve the signature of a method's return type.
+    ///
+    /// See [`get_method_return_type`](crate::get_method_return_type).
+    pub fn method_return_type(
+        &self,
+        interface_name: &str,
+        member_name: &str,
+        arg: impl Into<ArgSelector>,
+        shape: BodyShape,
+    ) -> Result<Signature> {
+        method_return_type_of(
+            self.interface(interface_name)?,
+            member_name,
+            &arg.into(),
+            shape,
+        )
+        .map_err(|e| e.searched_in(&self.roots))
+    }
+
+    /// Retrieve the signature of a method's argument type.
+    ///
+    /// See [`get_method_args_type`](crate::get_method_args_type).
+    pub fn method_args_type(
+        &self,
+        interface_name: &str,
+        member_name: &str,
+        arg: impl Into<ArgSelector>,
+        shape: BodyShape,
+    ) -> Result<Signature> {
+        method_args_type_of(
+            self.interface(interface_name)?,
+            member_name,
+            &arg.into(),
+            shape,
+        )
+        .map_err(|e| e.searched_in(&self.roots))
+    }
+
+    /// Retrieve the names of a method's arguments in one direction.
+    ///
+    /// See [`get_method_arg_names`](crate::get_method_arg_names).
+    pub fn method_arg_names(
+        &self,
+        interface_name: &str,
+        member_name: &str,
+        direction: ArgDirection,
+        arg: impl Into<ArgSelector>,
+    ) -> Result<Vec<Option<String>>> {
+        method_arg_names_of(
+            self.interface(interface_name)?,
+            member_name,
+            direction,
+            &arg.into(),
+        )
+        .map_err(|e| e.searched_in(&self.roots))
+    }
+
+    /// Retrieve the annotations of an interface.
+    ///
+    /// See [`get_interface_annotations`](crate::get_interface_annotations).
+    pub fn interface_annotations(&self, interface_name: &str) -> Result<Annotations> {
+        Ok(Annotations::from_xml(
+            self.interface(interface_name)?.annotations(),
+        ))
+    }
+
+    /// Retrieve the annotations of a method, signal or property.
+    ///
+    /// See [`get_member_annotations`](crate::get_member_annotations).
+    pub fn member_annotations(
+        &self,
+        interface_name: &str,
+        msg_type: MsgType,
+        member_name: &str,
+    ) -> Result<Annotations> {
+        member_annotations_of(self.interface(interface_name)?, msg_type, member_name)
+            .map_err(|e| e.searched_in(&self.roots))
+    }
+
+    /// Retrieve the annotations of the arguments of a method or signal.
+    ///
+    /// See [`get_arg_annotations`](crate::get_arg_annotations).
+    pub fn arg_annotations(
+        &self,
+        interface_name: &str,
+        msg_type: MsgType,
+        member_name: &str,
+        arg: impl Into<ArgSelector>,
+    ) -> Result<Vec<Annotations>> {
+        arg_annotations_of(
+            self.interface(interface_name)?,
+            msg_type,
+            member_name,
+            &arg.into(),
+        )
+        .map_err(|e| e.searched_in(&self.roots))
+    }
+
+    pub(crate) fn interface(&self, interface_name: &str) -> Result<&Interface<'static>> {
+        let (document_idx, interface_idx) =
+            self.interfaces
+                .get(interface_name)
+                .ok_or_else(|| InterfaceNotFound {
+                    name: interface_name.to_owned(),
+                    available: self.interface_names().map(ToOwned::to_owned).collect(),
+                    searched: self.roots.clone(),
+                })?;
+
+        Ok(&self.documents[*document_idx].node.interfaces()[*interface_idx])
+    }
+
+    fn document(&self, interface_name: &str) -> Option<&Document> {
+        let (document_idx, _) = self.interfaces.get(interface_name)?;
+        Some(&self.documents[*document_idx])
+    }
+
+    // The sorted, deduplicated names of the members of kind `msg_type`, optionally restricted to
+    // a single interface.
+    fn member_names(&self, msg_type: MsgType, interface: Option<&str>) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .members
+            .iter()
+            .filter(|((kind, _), offered_by)| {
+                *kind == msg_type
+                    && interface.is_none_or(|iface| offered_by.iter().any(|name| name == iface))
+            })
+            .map(|((_, name), _)| name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+}
+
+/// Read and parse the XML file at `path`.
+fn read_document(path: PathBuf) -> Result<Document> {
+    let file = std::fs::File::open(&path).map_err(|source| XmlFile {
+        path: path.clone(),
+        source,
+    })?;
+    let node = Node::from_reader(file).map_err(|source| XmlParse {
+        path: Some(path.clone()),
+        source,
+    })?;
+
+    Ok(Document {
+        root: None,
+        path: Some(path),
+        node,
+    })
+}
@@ -9,6 +9,12 @@
 //! These functions require that you provide the file path to the XML file, the interface name,
 //! and the interface member wherein the signature resides.
 //!
+//! The annotations of interfaces, members and arguments are available as [`Annotations`], with
+//! typed access to the well-known ones.
+//!
+//! When many lookups are made, [`ProtocolIndex`] parses a set of XML files once and answers the
+//! same queries from memory.
+//!
 //! Corresponding to each of these functions, macros are provided which do not
 //! require you to exactly point out where the signature is found. These will just search
 //! by interface member name.
@@ -17,25 +23,63 @@
 //!
 //! - `xml` or `XML`, the default path for `DBus` XML files - or is set by the
 //! - `LOCKSTEP_XML_PATH`, the env variable that overrides the default.
+//!
+//! A crate can also configure its XML directories, and other defaults, in `lockstep.toml` or in
+//! `[package.metadata.lockstep]`, see [`LockstepConfig`].
+//!
+//! `LOCKSTEP_XML_PATH` may list several directories, separated like `PATH`. These are searched
+//! in order, and [`Definition`] tells in which directory and file a member was found.
+//!
+//! The XML files are looked up recursively, in path order, and can be narrowed down with the
+//! comma separated glob patterns in `LOCKSTEP_XML_INCLUDE` and `LOCKSTEP_XML_EXCLUDE`. See
+//! [`XmlFilter`].
 #![doc(html_root_url = "https://docs.rs/zbus-lockstep/0.6.0")]
 #![allow(clippy::missing_errors_doc)]
 
+mod annotations;
+mod config;
+#[cfg(feature = "zbus")]
+mod conformance;
+mod diff;
+mod discovery;
 mod error;
+mod index;
 mod macros;
+mod names;
+mod policy;
+mod property;
+mod selector;
+mod shape;
 
 use std::{io::Read, str::FromStr};
 
-use LockstepError::{ArgumentNotFound, InterfaceNotFound, MemberNotFound, PropertyNotFound};
+use LockstepError::{
+    InterfaceNotFound, InvalidSignature, MemberNotFound, PropertyNotFound, XmlParse,
+};
+pub use annotations::{Annotations, EmitsChangedSignal};
+pub use config::LockstepConfig;
+#[cfg(feature = "zbus")]
+pub use conformance::{ConformanceReport, Difference, MemberMismatch, check_interface};
+pub use diff::{Mismatch, PathSegment, Relaxation, SignatureDiff};
+pub use discovery::{XmlFilter, discover_xml_files};
 pub use error::LockstepError;
+pub use index::{Definition, ProtocolIndex};
 pub use macros::resolve_xml_path;
+pub use names::{NameMismatch, compare_arg_names};
+pub use policy::SignaturePolicy;
+pub use property::PropertyDescriptor;
+pub use selector::ArgSelector;
+use selector::method_arg_direction;
+pub use shape::BodyShape;
 #[cfg(feature = "macros")]
-pub use zbus_lockstep_macros::validate;
+pub use zbus_lockstep_macros::{validate, validate_interface, validate_proxy};
 #[doc(hidden)]
 pub use zbus_xml;
 use zbus_xml::ArgDirection::{In, Out};
+pub use zbus_xml::{ArgDirection, PropertyAccess};
 use zvariant::Signature;
 
-type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+type Result<T> = std::result::Result<T, LockstepError>;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum MsgType {
@@ -46,8 +90,11 @@ pub enum MsgType {
 
 /// Retrieve a signal's body type signature from `DBus` XML.
 ///
-/// If you provide an argument name, then the signature of that argument is returned.
-/// If you do not provide an argument name, then the signature of all arguments is returned.
+/// `arg` selects the arguments whose signature is returned, see [`ArgSelector`]. Pass `None` for
+/// all arguments, an argument name, a position or a range of positions.
+///
+/// A signal without arguments has the unit signature, [`Signature::Unit`], which is also the
+/// signature of `()` and of unit structs.
 ///
 /// # Examples
 ///
@@ -86,42 +133,26 @@ pub enum MsgType {
 /// assert_eq!(&signature, DeviceEvent::SIGNATURE);
 /// ```
 pub fn get_signal_body_type(
-    mut xml: impl Read,
+    xml: impl Read,
     interface_name: &str,
     member_name: &str,
-    arg: Option<&str>,
+    arg: impl Into<ArgSelector>,
 ) -> Result<Signature> {
-    let node = zbus_xml::Node::from_reader(&mut xml)?;
+    get_signal_body_type_with_shape(xml, interface_name, member_name, arg, BodyShape::Auto)
+}
 
-    let interfaces = node.interfaces();
-    let interface = interfaces
-        .iter()
-        .find(|iface| iface.name() == interface_name)
-        .ok_or(InterfaceNotFound(interface_name.to_owned()))?;
+/// Like [`get_signal_body_type`], with the [`BodyShape`] the selected arguments are combined in.
+pub fn get_signal_body_type_with_shape(
+    mut xml: impl Read,
+    interface_name: &str,
+    member_name: &str,
+    arg: impl Into<ArgSelector>,
+    shape: BodyShape,
+) -> Result<Signature> {
+    let node = parse_node(&mut xml)?;
 
-    let signals = interface.signals();
-    let signal = signals
-        .iter()
-        .find(|signal| signal.name() == member_name)
-        .ok_or(MemberNotFound(member_name.to_owned()))?;
-
-    let signature = {
-        if let Some(arg_name) = arg {
-            let args = signal.args();
-            let arg = args
-                .iter()
-                .find(|arg| arg.name() == Some(arg_name))
-                .ok_or(ArgumentNotFound(arg_name.to_owned()))?;
-            arg.ty().to_string()
-        } else {
-            signal
-                .args()
-                .iter()
-                .map(|arg| arg.ty().to_string())
-                .collect::<String>()
-        }
-    };
-    Ok(Signature::from_str(&signature).map_err(|_| "Invalid signature")?)
+    let interface = find_interface(&node, interface_name)?;
+    signal_body_type_of(interface, member_name, &arg.into(), shape)
 }
 
 /// Retrieve the signature of a property's type from XML.
@@ -161,29 +192,34 @@ pub fn get_property_type(
     interface_name: &str,
     property_name: &str,
 ) -> Result<Signature> {
-    let node = zbus_xml::Node::from_reader(&mut xml)?;
+    let node = parse_node(&mut xml)?;
 
-    let interfaces = node.interfaces();
-    let interface = interfaces
-        .iter()
-        .find(|iface| iface.name() == interface_name)
-        .ok_or(InterfaceNotFound(interface_name.to_string()))?;
+    let interface = find_interface(&node, interface_name)?;
+    property_type_of(interface, property_name)
+}
 
-    let properties = interface.properties();
-    let property = properties
-        .iter()
-        .find(|property| property.name() == property_name)
-        .ok_or(PropertyNotFound(property_name.to_owned()))?;
+/// Retrieve the full description of a property from XML: its type, access mode and annotations.
+///
+/// See [`PropertyDescriptor`] for an example.
+pub fn get_property(
+    mut xml: impl Read,
+    interface_name: &str,
+    property_name: &str,
+) -> Result<PropertyDescriptor> {
+    let node = parse_node(&mut xml)?;
 
-    let signature = property.ty().to_string();
-    Ok(Signature::from_str(&signature).map_err(|_| "Invalid signature")?)
+    let interface = find_interface(&node, interface_name)?;
+    property_of(interface, property_name)
 }
 
 /// Retrieve the signature of a method's return type from XML.
 ///
-/// If you provide an argument name, then the signature of that argument is returned.
-/// If you do not provide an argument name, then the signature of all arguments is returned.
+/// `arg` selects the arguments whose signature is returned, see [`ArgSelector`]. Pass `None` for
+/// all arguments, an argument name, a position or a range of positions. Only `out` arguments can
+/// be selected; naming an `in` argument is an error.
 ///
+/// A method without `out` arguments returns `()`, its return type has the unit signature,
+/// [`Signature::Unit`].
 ///
 /// # Examples
 ///
@@ -224,52 +260,38 @@ pub fn get_property_type(
 /// assert_eq!(signature, *Role::SIGNATURE);
 /// ```
 pub fn get_method_return_type(
-    mut xml: impl Read,
+    xml: impl Read,
     interface_name: &str,
     member_name: &str,
-    arg_name: Option<&str>,
+    arg: impl Into<ArgSelector>,
 ) -> Result<Signature> {
-    let node = zbus_xml::Node::from_reader(&mut xml)?;
-
-    let interfaces = node.interfaces();
-    let interface = interfaces
-        .iter()
-        .find(|iface| iface.name() == interface_name)
-        .ok_or(InterfaceNotFound(interface_name.to_string()))?;
-
-    let methods = interface.methods();
-    let method = methods
-        .iter()
-        .find(|method| method.name() == member_name)
-        .ok_or(MemberNotFound(member_name.to_string()))?;
-
-    let args = method.args();
+    get_method_return_type_with_shape(xml, interface_name, member_name, arg, BodyShape::Auto)
+}
 
-    let signature = {
-        if let Some(known_arg_name) = arg_name {
-            args.iter()
-                .find(|arg| arg.name() == Some(known_arg_name))
-                .ok_or(ArgumentNotFound(known_arg_name.to_string()))?
-                .ty()
-                .to_string()
-        } else {
-            args.iter()
-                .filter(|arg| arg.direction() == Some(Out))
-                .map(|arg| arg.ty().to_string())
-                .collect::<String>()
-        }
-    };
+/// Like [`get_method_return_type`], with the [`BodyShape`] the selected arguments are combined in.
+pub fn get_method_return_type_with_shape(
+    mut xml: impl Read,
+    interface_name: &str,
+    member_name: &str,
+    arg: impl Into<ArgSelector>,
+    shape: BodyShape,
+) -> Result<Signature> {
+    let node = parse_node(&mut xml)?;
 
-    Ok(Signature::from_str(&signature).map_err(|_| "Invalid signature")?)
+    let interface = find_interface(&node, interface_name)?;
+    method_return_type_of(interface, member_name, &arg.into(), shape)
 }
 
 /// Retrieve the signature of a method's argument type from XML.
 ///
 /// Useful when one or more arguments, used to call a method, outline a useful type.
 ///
-/// If you provide an argument name, then the signature of that argument is returned.
-/// If you do not provide an argument name, then the signature of all arguments to the call is
-/// returned.
+/// `arg` selects the arguments whose signature is returned, see [`ArgSelector`]. Pass `None` for
+/// all arguments to the call, an argument name, a position or a range of positions. Only `in`
+/// arguments, or arguments without a direction, can be selected; naming an `out` argument is an
+/// error.
+///
+/// A method that takes no arguments has the unit signature, [`Signature::Unit`].
 ///
 /// # Examples
 ///
@@ -322,41 +344,489 @@ pub fn get_method_return_type(
 /// assert_eq!(&signature, Notification::SIGNATURE);
 /// ```
 pub fn get_method_args_type(
+    xml: impl Read,
+    interface_name: &str,
+    member_name: &str,
+    arg: impl Into<ArgSelector>,
+) -> Result<Signature> {
+    get_method_args_type_with_shape(xml, interface_name, member_name, arg, BodyShape::Auto)
+}
+
+/// Like [`get_method_args_type`], with the [`BodyShape`] the selected arguments are combined in.
+pub fn get_method_args_type_with_shape(
     mut xml: impl Read,
     interface_name: &str,
     member_name: &str,
-    arg_name: Option<&str>,
+    arg: impl Into<ArgSelector>,
+    shape: BodyShape,
 ) -> Result<Signature> {
-    let node = zbus_xml::Node::from_reader(&mut xml)?;
+    let node = parse_node(&mut xml)?;
 
-    let interfaces = node.interfaces();
-    let interface = interfaces
+    let interface = find_interface(&node, interface_name)?;
+    method_args_type_of(interface, member_name, &arg.into(), shape)
+}
+
+/// Retrieve the annotations of an interface from XML.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::{EmitsChangedSignal, get_interface_annotations};
+///
+/// let xml = r#"
+/// <node>
+///   <interface name="org.example.Node">
+///     <annotation name="org.freedesktop.DBus.Property.EmitsChangedSignal" value="const"/>
+///   </interface>
+/// </node>
+/// "#;
+///
+/// let annotations = get_interface_annotations(xml.as_bytes(), "org.example.Node").unwrap();
+/// assert_eq!(annotations.emits_changed_signal(), Some(EmitsChangedSignal::Const));
+/// ```
+pub fn get_interface_annotations(mut xml: impl Read, interface_name: &str) -> Result<Annotations> {
+    let node = parse_node(&mut xml)?;
+
+    let interface = find_interface(&node, interface_name)?;
+    Ok(Annotations::from_xml(interface.annotations()))
+}
+
+/// Retrieve the annotations of a method, signal or property from XML.
+///
+/// `msg_type` tells which kind of member `member_name` is.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::{MsgType, get_member_annotations};
+///
+/// let xml = r#"
+/// <node>
+///   <interface name="org.example.Node">
+///     <signal name="Alert">
+///       <annotation name="org.freedesktop.DBus.Deprecated" value="true"/>
+///     </signal>
+///   </interface>
+/// </node>
+/// "#;
+///
+/// let annotations =
+///     get_member_annotations(xml.as_bytes(), "org.example.Node", MsgType::Signal, "Alert")
+///         .unwrap();
+/// assert!(annotations.deprecated());
+/// ```
+pub fn get_member_annotations(
+    mut xml: impl Read,
+    interface_name: &str,
+    msg_type: MsgType,
+    member_name: &str,
+) -> Result<Annotations> {
+    let node = parse_node(&mut xml)?;
+
+    let interface = find_interface(&node, interface_name)?;
+    member_annotations_of(interface, msg_type, member_name)
+}
+
+/// Retrieve the annotations of the arguments of a method or signal from XML.
+///
+/// `arg` selects the arguments, see [`ArgSelector`]. The annotations of each selected argument are
+/// returned in the order selected. Method arguments of either direction can be selected.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::{MsgType, get_arg_annotations};
+///
+/// let xml = r#"
+/// <node>
+///   <interface name="org.a11y.atspi.Cache">
+///     <signal name="AddAccessible">
+///       <arg name="nodeAdded" type="((so)(so)(so)iiassusau)">
+///         <annotation name="org.qtproject.QtDBus.QtTypeName" value="QSpiAccessibleCacheItem"/>
+///       </arg>
+///     </signal>
+///   </interface>
+/// </node>
+/// "#;
+///
+/// let annotations = get_arg_annotations(
+///     xml.as_bytes(),
+///     "org.a11y.atspi.Cache",
+///     MsgType::Signal,
+///     "AddAccessible",
+///     "nodeAdded",
+/// )
+/// .unwrap();
+/// assert_eq!(
+///     annotations[0].get("org.qtproject.QtDBus.QtTypeName"),
+///     Some("QSpiAccessibleCacheItem")
+/// );
+/// ```
+pub fn get_arg_annotations(
+    mut xml: impl Read,
+    interface_name: &str,
+    msg_type: MsgType,
+    member_name: &str,
+    arg: impl Into<ArgSelector>,
+) -> Result<Vec<Annotations>> {
+    let node = parse_node(&mut xml)?;
+
+    let interface = find_interface(&node, interface_name)?;
+    arg_annotations_of(interface, msg_type, member_name, &arg.into())
+}
+
+/// Retrieve the names of a signal's arguments from XML.
+///
+/// `arg` selects the arguments, see [`ArgSelector`]. Arguments without a name are `None`. Use
+/// [`compare_arg_names`] to check them against the field names of a struct.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::get_signal_arg_names;
+///
+/// let xml = r#"
+/// <node>
+///   <interface name="org.example.Node">
+///     <signal name="Alert">
+///       <arg name="urgent" type="b"/>
+///       <arg type="s"/>
+///     </signal>
+///   </interface>
+/// </node>
+/// "#;
+///
+/// let names = get_signal_arg_names(xml.as_bytes(), "org.example.Node", "Alert", None).unwrap();
+/// assert_eq!(names, [Some("urgent".to_string()), None]);
+/// ```
+pub fn get_signal_arg_names(
+    mut xml: impl Read,
+    interface_name: &str,
+    member_name: &str,
+    arg: impl Into<ArgSelector>,
+) -> Result<Vec<Option<String>>> {
+    let node = parse_node(&mut xml)?;
+
+    let interface = find_interface(&node, interface_name)?;
+    signal_arg_names_of(interface, member_name, &arg.into())
+}
+
+/// Retrieve the names of a method's arguments in one direction from XML.
+///
+/// `direction` chooses between the arguments to the call, [`ArgDirection::In`], and those of the
+/// reply, [`ArgDirection::Out`]. Arguments without a direction are `in` arguments. `arg` selects
+/// among them, see [`ArgSelector`]. Arguments without a name are `None`.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::{ArgDirection, get_method_arg_names};
+///
+/// let xml = r#"
+/// <node>
+///   <interface name="org.example.Node">
+///     <method name="RequestName">
+///       <arg name="name" type="s" direction="in"/>
+///       <arg name="flags" type="u"/>
+///       <arg name="reply" type="u" direction="out"/>
+///     </method>
+///   </interface>
+/// </node>
+/// "#;
+///
+/// let names = get_method_arg_names(
+///     xml.as_bytes(),
+///     "org.example.Node",
+///     "RequestName",
+///     ArgDirection::In,
+///     None,
+/// )
+/// .unwrap();
+/// assert_eq!(names, [Some("name".to_string()), Some("flags".to_string())]);
+/// ```
+pub fn get_method_arg_names(
+    mut xml: impl Read,
+    interface_name: &str,
+    member_name: &str,
+    direction: ArgDirection,
+    arg: impl Into<ArgSelector>,
+) -> Result<Vec<Option<String>>> {
+    let node = parse_node(&mut xml)?;
+
+    let interface = find_interface(&node, interface_name)?;
+    method_arg_names_of(interface, member_name, direction, &arg.into())
+}
+
+/// Retrieve the names of the selected arguments of a method in one direction from an interface.
+pub(crate) fn method_arg_names_of(
+    interface: &zbus_xml::Interface<'_>,
+    member_name: &str,
+    direction: ArgDirection,
+    arg: &ArgSelector,
+) -> Result<Vec<Option<String>>> {
+    let args = find_method(interface, member_name)?.args();
+    let selectable: Vec<&zbus_xml::Arg> = args
         .iter()
-        .find(|iface| iface.name() == interface_name)
-        .ok_or(InterfaceNotFound(interface_name.to_owned()))?;
+        .filter(|arg| method_arg_direction(arg) == direction)
+        .collect();
+    let selected = arg.select(
+        &interface.name(),
+        member_name,
+        args,
+        &selectable,
+        Some(direction),
+    )?;
+
+    Ok(selected
+        .iter()
+        .map(|arg| arg.name().map(ToOwned::to_owned))
+        .collect())
+}
+
+/// Retrieve the names of the selected arguments of a signal from an interface.
+pub(crate) fn signal_arg_names_of(
+    interface: &zbus_xml::Interface<'_>,
+    member_name: &str,
+    arg: &ArgSelector,
+) -> Result<Vec<Option<String>>> {
+    let args = find_signal(interface, member_name)?.args();
+    let selectable: Vec<&zbus_xml::Arg> = args.iter().collect();
+    let selected = arg.select(&interface.name(), member_name, args, &selectable, None)?;
+
+    Ok(selected
+        .iter()
+        .map(|arg| arg.name().map(ToOwned::to_owned))
+        .collect())
+}
+
+/// Retrieve the annotations of a method, signal or property from an interface.
+pub(crate) fn member_annotations_of(
+    interface: &zbus_xml::Interface<'_>,
+    msg_type: MsgType,
+    member_name: &str,
+) -> Result<Annotations> {
+    let annotations = match msg_type {
+        MsgType::Method => find_method(interface, member_name)?.annotations(),
+        MsgType::Signal => find_signal(interface, member_name)?.annotations(),
+        MsgType::Property => find_property(interface, member_name)?.annotations(),
+    };
+
+    Ok(Annotations::from_xml(annotations))
+}
+
+/// Retrieve the annotations of the selected arguments of a method or signal from an interface.
+pub(crate) fn arg_annotations_of(
+    interface: &zbus_xml::Interface<'_>,
+    msg_type: MsgType,
+    member_name: &str,
+    arg: &ArgSelector,
+) -> Result<Vec<Annotations>> {
+    let args = match msg_type {
+        MsgType::Method => find_method(interface, member_name)?.args(),
+        MsgType::Signal => find_signal(interface, member_name)?.args(),
+        // Properties have no arguments, but a missing property is reported as such.
+        MsgType::Property => {
+            find_property(interface, member_name)?;
+            &[]
+        }
+    };
+
+    let selectable: Vec<&zbus_xml::Arg> = args.iter().collect();
+    let selected = arg.select(&interface.name(), member_name, args, &selectable, None)?;
 
+    Ok(selected
+        .iter()
+        .map(|arg| Annotations::from_xml(arg.annotations()))
+        .collect())
+}
+
+/// Retrieve a signal's body type signature from an interface.
+pub(crate) fn signal_body_type_of(
+    interface: &zbus_xml::Interface<'_>,
+    member_name: &str,
+    arg: &ArgSelector,
+    shape: BodyShape,
+) -> Result<Signature> {
+    let signal = find_signal(interface, member_name)?;
+
+    let args = signal.args();
+    let selectable: Vec<&zbus_xml::Arg> = args.iter().collect();
+    let selected = arg.select(&interface.name(), member_name, args, &selectable, None)?;
+
+    signature_of(&selected, &interface.name(), member_name, arg, shape)
+}
+
+/// Retrieve the signature of a property's type from an interface.
+pub(crate) fn property_type_of(
+    interface: &zbus_xml::Interface<'_>,
+    property_name: &str,
+) -> Result<Signature> {
+    let property = find_property(interface, property_name)?;
+
+    let signature = property.ty().to_string();
+    parse_signature(
+        &signature,
+        &interface.name(),
+        property_name,
+        &ArgSelector::All,
+    )
+}
+
+/// Retrieve the full description of a property from an interface.
+pub(crate) fn property_of(
+    interface: &zbus_xml::Interface<'_>,
+    property_name: &str,
+) -> Result<PropertyDescriptor> {
+    let property = find_property(interface, property_name)?;
+
+    Ok(PropertyDescriptor {
+        name: property_name.to_owned(),
+        signature: property_type_of(interface, property_name)?,
+        access: property.access(),
+        annotations: Annotations::from_xml(property.annotations()),
+    })
+}
+
+/// Retrieve the signature of a method's return type from an interface.
+pub(crate) fn method_return_type_of(
+    interface: &zbus_xml::Interface<'_>,
+    member_name: &str,
+    arg: &ArgSelector,
+    shape: BodyShape,
+) -> Result<Signature> {
+    let method = find_method(interface, member_name)?;
+
+    let args = method.args();
+    let selectable: Vec<&zbus_xml::Arg> = args
+        .iter()
+        .filter(|arg| method_arg_direction(arg) == Out)
+        .collect();
+    let selected = arg.select(&interface.name(), member_name, args, &selectable, Some(Out))?;
+
+    signature_of(&selected, &interface.name(), member_name, arg, shape)
+}
+
+/// Retrieve the signature of a method's argument type from an interface.
+pub(crate) fn method_args_type_of(
+    interface: &zbus_xml::Interface<'_>,
+    member_name: &str,
+    arg: &ArgSelector,
+    shape: BodyShape,
+) -> Result<Signature> {
+    let method = find_method(interface, member_name)?;
+
+    let args = method.args();
+    let selectable: Vec<&zbus_xml::Arg> = args
+        .iter()
+        .filter(|arg| method_arg_direction(arg) == In)
+        .collect();
+    let selected = arg.select(&interface.name(), member_name, args, &selectable, Some(In))?;
+
+    signature_of(&selected, &interface.name(), member_name, arg, shape)
+}
+
+/// Find the method named `member_name` in `interface`.
+fn find_method<'i>(
+    interface: &'i zbus_xml::Interface<'_>,
+    member_name: &str,
+) -> Result<&'i zbus_xml::Method<'i>> {
     let methods = interface.methods();
-    let method = methods
+    methods
         .iter()
         .find(|method| method.name() == member_name)
-        .ok_or(member_name.to_owned())?;
+        .ok_or_else(|| MemberNotFound {
+            name: member_name.to_owned(),
+            available: methods.iter().map(|m| m.name().to_string()).collect(),
+            searched: Vec::new(),
+        })
+}
 
-    let args = method.args();
+/// Find the signal named `member_name` in `interface`.
+fn find_signal<'i>(
+    interface: &'i zbus_xml::Interface<'_>,
+    member_name: &str,
+) -> Result<&'i zbus_xml::Signal<'i>> {
+    let signals = interface.signals();
+    signals
+        .iter()
+        .find(|signal| signal.name() == member_name)
+        .ok_or_else(|| MemberNotFound {
+            name: member_name.to_owned(),
+            available: signals.iter().map(|s| s.name().to_string()).collect(),
+            searched: Vec::new(),
+        })
+}
 
-    let signature = if let Some(known_arg_name) = arg_name {
-        args.iter()
-            .find(|arg| arg.name() == Some(known_arg_name))
-            .ok_or(ArgumentNotFound(known_arg_name.to_string()))?
-            .ty()
-            .to_string()
-    } else {
-        args.iter()
-            .filter(|arg| arg.direction() == Some(In))
-            .map(|arg| arg.ty().to_string())
-            .collect::<String>()
-    };
+/// Find the property named `property_name` in `interface`.
+fn find_property<'i>(
+    interface: &'i zbus_xml::Interface<'_>,
+    property_name: &str,
+) -> Result<&'i zbus_xml::Property<'i>> {
+    let properties = interface.properties();
+    properties
+        .iter()
+        .find(|property| property.name() == property_name)
+        .ok_or_else(|| PropertyNotFound {
+            name: property_name.to_owned(),
+            available: properties.iter().map(|p| p.name().to_string()).collect(),
+            searched: Vec::new(),
+        })
+}
+
+/// Find the interface named `interface_name` in `node`.
+pub(crate) fn find_interface<'n>(
+    node: &'n zbus_xml::Node<'static>,
+    interface_name: &str,
+) -> Result<&'n zbus_xml::Interface<'static>> {
+    let interfaces = node.interfaces();
+    interfaces
+        .iter()
+        .find(|iface| iface.name() == interface_name)
+        .ok_or_else(|| InterfaceNotFound {
+            name: interface_name.to_owned(),
+            available: interfaces.iter().map(|i| i.name().to_string()).collect(),
+            searched: Vec::new(),
+        })
+}
+
+/// The signature of the `selected` arguments, combined according to `shape`.
+///
+/// No arguments at all is the unit signature, whatever the shape.
+pub(crate) fn signature_of(
+    selected: &[&zbus_xml::Arg],
+    interface_name: &str,
+    member_name: &str,
+    arg: &ArgSelector,
+    shape: BodyShape,
+) -> Result<Signature> {
+    if selected.is_empty() {
+        return Ok(Signature::Unit);
+    }
 
-    Ok(Signature::from_str(&signature).map_err(|_| "Invalid signature")?)
+    let types: Vec<String> = selected.iter().map(|arg| arg.ty().to_string()).collect();
+    let signature = shape.combine(types.iter().map(String::as_str));
+
+    parse_signature(&signature, interface_name, member_name, arg)
+}
+
+/// Parse the introspection XML document from `xml`.
+fn parse_node(xml: impl Read) -> Result<zbus_xml::Node<'static>> {
+    zbus_xml::Node::from_reader(xml).map_err(|source| XmlParse { path: None, source })
+}
+
+/// Parse `raw` as a signature, keeping track of where it was found should it be invalid.
+fn parse_signature(
+    raw: &str,
+    interface_name: &str,
+    member_name: &str,
+    arg: &ArgSelector,
+) -> Result<Signature> {
+    Signature::from_str(raw).map_err(|_| InvalidSignature {
+        interface: interface_name.to_owned(),
+        member: member_name.to_owned(),
+        arg: arg.describe(),
+        raw: raw.to_owned(),
+    })
 }
 
 #[cfg(test)]
@@ -366,7 +836,11 @@ mod test {
     use tempfile::tempfile;
     use zvariant::{OwnedObjectPath, Type};
 
-    use crate::get_signal_body_type;
+    use crate::{
+        ArgSelector, BodyShape, LockstepError, MsgType, get_member_annotations,
+        get_method_args_type, get_method_return_type, get_signal_body_type,
+        get_signal_body_type_with_shape,
+    };
 
     #[test]
     fn test_get_signature_of_cache_add_accessible() {
@@ -410,5 +884,198 @@ mod test {
 
         let signature = get_signal_body_type(xml_file, interface_name, member_name, None).unwrap();
         assert_eq!(signature, *CacheItem::SIGNATURE);
+        let annotations =
+            get_member_annotations(xml.as_bytes(), interface_name, MsgType::Signal, member_name)
+                .unwrap();
+        assert_eq!(
+            annotations.get("org.qtproject.QtDBus.QtTypeName.In0"),
+            Some("QSpiAccessibleCacheItem")
+        );
+    }
+
+    #[test]
+    fn test_malformed_xml_is_reported_as_xml_parse_error() {
+        let xml = r#"<node><interface name="org.example.Node"></node>"#;
+
+        let err = get_signal_body_type(xml.as_bytes(), "org.example.Node", "AddNode", None)
+            .expect_err("Malformed XML should not parse");
+        assert!(matches!(err, LockstepError::XmlParse { path: None, .. }));
+    }
+
+    #[test]
+    fn test_missing_method_is_reported_as_member_not_found() {
+        let xml = r#"<node>
+            <interface name="org.example.Node">
+                <method name="RequestName">
+                    <arg direction="in" name="apple" type="s"/>
+                </method>
+            </interface>
+        </node>"#;
+
+        let err = get_method_args_type(xml.as_bytes(), "org.example.Node", "ReleaseName", None)
+            .expect_err("Method should not be found");
+        assert!(matches!(err, LockstepError::MemberNotFound { name, .. } if name == "ReleaseName"));
+    }
+
+    #[test]
+    fn test_member_not_found_suggests_close_names() {
+        let xml = r#"<node>
+            <interface name="org.a11y.atspi.Event.Object">
+                <signal name="StateChanged">
+                    <arg name="event" type="siiva{sv}"/>
+                </signal>
+                <signal name="BoundsChanged">
+                    <arg name="event" type="siiva{sv}"/>
+                </signal>
+            </interface>
+        </node>"#;
+
+        let err = get_signal_body_type(
+            xml.as_bytes(),
+            "org.a11y.atspi.Event.Object",
+            "StateChange",
+            None,
+        )
+        .expect_err("Signal should not be found");
+        assert_eq!(err.suggestions(), vec!["StateChanged"]);
+        assert_eq!(
+            err.to_string(),
+            "Member \"StateChange\" not found. Did you mean \"StateChanged\"?"
+        );
+    }
+
+    #[test]
+    fn test_unnamed_arguments_are_selected_by_position() {
+        let xml = r#"<node>
+            <interface name="org.freedesktop.DBus">
+                <method name="GetConnectionUnixUser">
+                    <arg direction="in" type="s"/>
+                    <arg direction="out" type="u"/>
+                </method>
+            </interface>
+        </node>"#;
+
+        let interface = "org.freedesktop.DBus";
+        let member = "GetConnectionUnixUser";
+
+        let signature = get_method_args_type(xml.as_bytes(), interface, member, 0).unwrap();
+        assert_eq!(signature, "s");
+        let signature = get_method_return_type(xml.as_bytes(), interface, member, 0).unwrap();
+        assert_eq!(signature, "u");
+
+        // Open ranges and ranges up to `usize::MAX` select up to the last argument.
+        for arg in [ArgSelector::from(0..), ArgSelector::from(0..=usize::MAX)] {
+            let signature = get_method_return_type(xml.as_bytes(), interface, member, arg).unwrap();
+            assert_eq!(signature, "u");
+        }
+
+        let err = get_method_return_type(xml.as_bytes(), interface, member, ArgSelector::Index(1))
+            .expect_err("There is only one out argument");
+        assert!(matches!(
+            err,
+            LockstepError::ArgumentIndexOutOfRange {
+                index: 1,
+                count: 1,
+                ..
+            }
+        ));
+        assert_eq!(
+            err.to_string(),
+            "Argument #1 of org.freedesktop.DBus.GetConnectionUnixUser is out of range, the \
+             member has 1 argument(s)."
+        );
+
+        let err = get_method_args_type(xml.as_bytes(), interface, member, 0..2)
+            .expect_err("There is only one in argument");
+        assert_eq!(
+            err.to_string(),
+            "Arguments #0..#2 of org.freedesktop.DBus.GetConnectionUnixUser are out of range, \
+             the member has 1 argument(s)."
+        );
+
+        #[allow(clippy::reversed_empty_ranges)]
+        let reversed = 1..0;
+        let err = get_method_args_type(xml.as_bytes(), interface, member, reversed.clone())
+            .expect_err("The range is reversed");
+        assert!(matches!(
+            err,
+            LockstepError::ArgumentRangeOutOfRange { ref range, count: 1, .. } if *range == reversed
+        ));
+        assert_eq!(
+            err.to_string(),
+            "Arguments #1..#0 of org.freedesktop.DBus.GetConnectionUnixUser are a reversed range."
+        );
+    }
+
+    #[test]
+    fn test_named_method_arguments_respect_direction() {
+        let xml = r#"<node>
+            <interface name="org.example.Settings">
+                <method name="Swap">
+                    <arg name="value" type="s" direction="in"/>
+                    <arg name="value" type="v" direction="out"/>
+                    <arg name="previous" type="v" direction="out"/>
+                </method>
+                <method name="Reset">
+                    <arg name="key" type="s"/>
+                </method>
+            </interface>
+        </node>"#;
+
+        let interface = "org.example.Settings";
+
+        // In and out arguments share a name, each lookup finds its own.
+        let signature = get_method_args_type(xml.as_bytes(), interface, "Swap", "value").unwrap();
+        assert_eq!(signature, "s");
+        let signature = get_method_return_type(xml.as_bytes(), interface, "Swap", "value").unwrap();
+        assert_eq!(signature, "v");
+
+        let err = get_method_args_type(xml.as_bytes(), interface, "Swap", "previous")
+            .expect_err("`previous` is an out argument");
+        assert!(matches!(err, LockstepError::ArgumentDirection { .. }));
+        assert_eq!(
+            err.to_string(),
+            "Argument \"previous\" has direction \"out\", expected \"in\"."
+        );
+
+        // Method arguments without a direction are `in` arguments.
+        let signature = get_method_args_type(xml.as_bytes(), interface, "Reset", None).unwrap();
+        assert_eq!(signature, "s");
+        let signature = get_method_args_type(xml.as_bytes(), interface, "Reset", "key").unwrap();
+        assert_eq!(signature, "s");
+    }
+
+    #[test]
+    fn test_members_without_arguments_have_unit_signature() {
+        let xml = r#"
+        <node>
+          <interface name="org.example.Zero">
+            <signal name="Reset"/>
+            <method name="Ping"/>
+            <method name="Quit">
+              <arg name="code" direction="in" type="i"/>
+            </method>
+          </interface>
+        </node>
+        "#;
+        let interface = "org.example.Zero";
+
+        #[derive(Type)]
+        struct ResetSignal;
+
+        for shape in [BodyShape::Flattened, BodyShape::Struct, BodyShape::Auto] {
+            let signature =
+                get_signal_body_type_with_shape(xml.as_bytes(), interface, "Reset", None, shape)
+                    .unwrap();
+            assert_eq!(&signature, ResetSignal::SIGNATURE);
+            assert_eq!(&signature, <()>::SIGNATURE);
+        }
+
+        let signature = get_method_args_type(xml.as_bytes(), interface, "Ping", None).unwrap();
+        assert_eq!(&signature, <()>::SIGNATURE);
+
+        // A method with only `in` arguments returns `()`.
+        let signature = get_method_return_type(xml.as_bytes(), interface, "Quit", None).unwrap();
+        assert_eq!(&signature, <()>::SIGNATURE);
     }
 }
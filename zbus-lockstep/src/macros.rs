@@ -4,16 +4,23 @@
 
 use std::{fs, path::PathBuf, str::FromStr};
 
-use crate::Result;
+use zbus_lockstep_common::{config::Config, roots::resolve_xml_roots};
 
-/// Resolve XML path from either:
+use crate::{LockstepError, Result};
+
+/// Resolve the XML definitions directories, the roots, from either:
 ///
-/// This function tries to resolve the XML path from the following sources, in order:
+/// This function tries to resolve the XML roots from the following sources, in order:
 ///
 /// 1. Environment variable (`LOCKSTEP_XML_PATH`)
 /// 2. Provided argument
-/// 3. Default location (`xml/`, `XML/`, `../xml` or `../XML` or `<crate_name>/xml` or
-///    `<crate_name>/XML`)
+/// 3. The `xml` roots of the crate's configuration, see [`LockstepConfig`](crate::LockstepConfig)
+/// 4. Default location (`xml/`, `XML/`, `../xml` or `../XML` or `<crate_name>/xml` or
+///    `<crate_name>/XML`), only without configured roots
+///
+/// The environment variable and the argument may list several directories, separated like `PATH`:
+/// by `:`, or `;` on Windows. The roots are returned in that order, which is the order in which
+/// they are searched. Each must exist.
 ///
 /// # Example
 ///
@@ -22,140 +29,45 @@ use crate::Result;
 /// # use std::path::PathBuf;
 /// # fn main() {
 ///
-/// let xml_path = resolve_xml_path(None).unwrap_or_else(|e| panic!("Failed to resolve XML path: {e}"));
-/// assert_eq!(xml_path, PathBuf::from("../xml").canonicalize().unwrap());
+/// let xml_paths = resolve_xml_path(None).unwrap_or_else(|e| panic!("Failed to resolve XML path: {e}"));
+/// assert_eq!(xml_paths, [PathBuf::from("../xml").canonicalize().unwrap()]);
+///
+/// let roots = std::env::join_paths(["../xml", "../zbus-lockstep"]).unwrap();
+/// let xml_paths = resolve_xml_path(roots.to_str()).unwrap();
+/// assert_eq!(xml_paths.len(), 2);
 /// # }
 /// ```
-pub fn resolve_xml_path(xml: Option<&str>) -> Result<PathBuf> {
-    // `LOCKSTEP_XML_PATH` emv variable has precedence over the argument and default paths
-    if let Ok(env_path) = std::env::var("LOCKSTEP_XML_PATH") {
-        return Ok(PathBuf::from(env_path).canonicalize()?);
-    }
-
-    // Provided argument has precedence over the default paths
-    if let Some(arg_path) = xml {
-        return Ok(PathBuf::from(arg_path).canonicalize()?);
-    }
+pub fn resolve_xml_path(xml: Option<&str>) -> Result<Vec<PathBuf>> {
+    let config = Config::shared().map_err(|err| LockstepError::from(err.clone()))?;
+    let args: Vec<String> = xml.map(ToOwned::to_owned).into_iter().collect();
 
-    // Fallback to the default paths:
-
-    let current_dir = PathBuf::from(
-        std::env::var("CARGO_MANIFEST_DIR")
-            .map_err(|e| format!("CARGO_MANIFEST_DIR environment variable is not set: {e}"))?,
-    );
-    let crate_name = std::env::var("CARGO_PKG_NAME").unwrap_or_else(|_| String::from("unknown"));
-
-    let paths_to_try = [
-        current_dir.join("xml"),
-        current_dir.join("XML"),
-        current_dir.join("../xml"),
-        current_dir.join("../XML"),
-        current_dir.join(&crate_name).join("xml"),
-        current_dir.join(&crate_name).join("XML"),
-    ];
-
-    for path in paths_to_try {
-        if path.exists() {
-            return Ok(path.canonicalize()?);
-        }
-    }
-
-    Err(format!(
-        "No XML path provided and default XML path not found. Current directory: \"{}\"",
-        current_dir.display()
-    )
-    .into())
+    Ok(resolve_xml_roots(&args, config)?)
 }
 
 /// A generic helper to find the file path and interface name of a member.
 #[doc(hidden)]
 #[macro_export]
 macro_rules! find_definition_in_dbus_xml {
-    ($xml_path_buf:expr, $member:expr, $iface:expr, $msg_type:expr) => {{
-    use $crate::MsgType;
-
-    let xml_path_buf: std::path::PathBuf = $xml_path_buf;
-    let member: &str = $member;
-    let iface: Option<String> = $iface;
-    let msg_type: MsgType = $msg_type;
-
-    let mut xml_file_path = None;
-    let mut interface_name = None;
-
-    let read_dir = std::fs::read_dir(&xml_path_buf).expect("Failed to read XML directory");
-
-    // Walk the XML files in the directory.
-    for entry in read_dir {
-        let entry = entry.expect("Failed to read entry");
-
-        // Skip directories and non-XML files.
-        if entry.path().is_dir() || entry.path().extension().unwrap() != "xml" {
-            continue;
-        }
-
-        let entry_path = entry.path().clone();
-        let file = std::fs::File::open(entry.path()).expect("Failed to open file");
-        let node = $crate::zbus_xml::Node::from_reader(file).expect("Failed to parse XML file");
-
-        for interface in node.interfaces() {
-            // If called with an `iface` arg, skip he interfaces that do not match.
-            if iface.is_some() && interface.name().as_str() != iface.clone().unwrap()  {
-                continue;
-            }
-
-            match msg_type {
-                MsgType::Method => {
-                    for dbus_item in interface.methods() {
-                        if dbus_item.name() == member {
-                            if interface_name.is_some() {
-                                panic!(
-                                    "Multiple interfaces offer the same {:?} member: {}, please specify the interface name.",
-                                    msg_type, member
-                                );
-                            }
-                            interface_name = Some(interface.name().to_string());
-                            xml_file_path = Some(entry_path.clone());
-                        }
-                    }
-                }
-                MsgType::Signal => {
-                    for dbus_item in interface.signals() {
-                        if dbus_item.name() == member {
-                            if interface_name.is_some() {
-                                panic!(
-                                    "Multiple interfaces offer the same {:?} member: {}, please specify the interface name.",
-                                    msg_type, member
-                                );
-                            }
-                            interface_name = Some(interface.name().to_string());
-                            xml_file_path = Some(entry_path.clone());
-                        }
-                    }
-                }
-                MsgType::Property => {
-                    for dbus_item in interface.properties() {
-                        if dbus_item.name() == member {
-                            if interface_name.is_some() {
-                                panic!(
-                                    "Multiple interfaces offer the same {:?} member: {}, please specify the interface name.",
-                                    msg_type, member
-                                );
-                            }
-                            interface_name = Some(interface.name().to_string());
-                            xml_file_path = Some(entry_path.clone());
-                        }
-                    }
-                }
-            };
-        }
-    }
-
-    // If the interface member was not found, return an error.
-    if xml_file_path.is_none() {
-        panic!("Member not found in XML files.");
-    }
-
-    (xml_file_path.unwrap(), interface_name.unwrap())
+    ($xml_paths:expr, $member:expr, $iface:expr, $msg_type:expr) => {{
+        let xml_paths: Vec<std::path::PathBuf> = $xml_paths;
+        let member: &str = $member;
+        let iface: Option<String> = $iface;
+        let msg_type: $crate::MsgType = $msg_type;
+
+        let index = $crate::ProtocolIndex::from_dirs(&xml_paths)
+            .unwrap_or_else(|err| panic!("Failed to index XML directories: {err}"));
+
+        // If the interface member was not found, or found more than once, fail.
+        let definition = index
+            .locate(msg_type, member, iface.as_deref())
+            .unwrap_or_else(|err| panic!("{err}"));
+
+        let xml_file_path = definition
+            .path
+            .expect("Definitions read from a directory have a file path")
+            .to_path_buf();
+
+        (xml_file_path, definition.interface.to_string())
     }};
 }
 
@@ -188,7 +100,8 @@ macro_rules! find_definition_in_dbus_xml {
 /// assert_eq!(&sig, &Signature::from_str("u").expect("Valid signature pattern"));
 /// ```
 /// The macro supports colling arguments with identifiers as well as without.
-/// The macro may also be called with an interface name or interface and argument name:
+/// The macro may also be called with an interface name or interface and argument selector,
+/// which can be anything that converts into an [`ArgSelector`](crate::ArgSelector):
 ///
 /// ```rust
 /// # use zbus_lockstep::{method_return_signature};
@@ -197,6 +110,10 @@ macro_rules! find_definition_in_dbus_xml {
 /// // or alternatively
 ///
 /// let _sig = method_return_signature!(member: "RequestName", interface: "org.example.Node", argument: "grape");
+///
+/// // or choose the shape of the signature, see [`BodyShape`](crate::BodyShape)
+///
+/// let _sig = method_return_signature!("RequestName", "org.example.Node", "grape", zbus_lockstep::BodyShape::Struct);
 /// ```
 #[macro_export]
 macro_rules! method_return_signature {
@@ -204,17 +121,28 @@ macro_rules! method_return_signature {
         use $crate::MsgType;
         let member = $member;
 
-        // Looking for default path or path specified by environment variable.
-        let xml_path = $crate::resolve_xml_path(None)
-            .unwrap_or_else(|err| panic!("Failed to resolve XML path: {err}"));
-
-        // Find the definition of the method in the XML specification.
-        let (file_path, interface_name) =
-            $crate::find_definition_in_dbus_xml!(xml_path, member, None, MsgType::Method);
-
-        let file = std::fs::File::open(file_path).expect("Failed to open file");
-        $crate::get_method_return_type(file, &interface_name, member, None)
-            .expect("Failed to get method arguments type signature")
+        let index = $crate::ProtocolIndex::shared()
+            .unwrap_or_else(|err| panic!("Failed to index XML definitions: {err}"));
+
+        // Find the definition of the member in the XML specification.
+        let definition = index
+            .locate(
+                MsgType::Method,
+                member,
+                $crate::LockstepConfig::default_interface(),
+            )
+            .unwrap_or_else(|err| panic!("{err}"));
+
+        index
+            .method_return_type(
+                definition.interface,
+                member,
+                None,
+                $crate::LockstepConfig::default_shape(),
+            )
+            .unwrap_or_else(|err| {
+                panic!("Failed to get method arguments type signature from {definition}: {err}")
+            })
     }};
 
     (member: $member:expr) => {
@@ -227,46 +155,69 @@ macro_rules! method_return_signature {
 
         let interface = Some($interface.to_string());
 
-        // Looking for default path or path specified by environment variable.
-        let xml_path = $crate::resolve_xml_path(None)
-            .unwrap_or_else(|err| panic!("Failed to resolve XML path: {err}"));
-
-        // Find the definition of the method in the XML specification.
-        let (file_path, interface_name) =
-            $crate::find_definition_in_dbus_xml!(xml_path, member, interface, MsgType::Method);
-
-        let file = std::fs::File::open(file_path).expect("Failed to open file");
-        $crate::get_method_return_type(file, &interface_name, member, None)
-            .expect("Failed to get method arguments type signature")
+        let index = $crate::ProtocolIndex::shared()
+            .unwrap_or_else(|err| panic!("Failed to index XML definitions: {err}"));
+
+        // Find the definition of the member in the XML specification.
+        let definition = index
+            .locate(MsgType::Method, member, interface.as_deref())
+            .unwrap_or_else(|err| panic!("{err}"));
+
+        index
+            .method_return_type(
+                definition.interface,
+                member,
+                None,
+                $crate::LockstepConfig::default_shape(),
+            )
+            .unwrap_or_else(|err| {
+                panic!("Failed to get method arguments type signature from {definition}: {err}")
+            })
     }};
 
     (member: $member:expr, interface: $interface:expr) => {
         $crate::method_return_signature!($member, $interface)
     };
 
-    ($member:expr, $interface:expr, $argument:expr) => {{
+    ($member:expr, $interface:expr, $argument:expr) => {
+        $crate::method_return_signature!(
+            $member,
+            $interface,
+            $argument,
+            $crate::LockstepConfig::default_shape()
+        )
+    };
+
+    ($member:expr, $interface:expr, $argument:expr, $shape:expr) => {{
         let member = $member;
         use $crate::MsgType;
 
         let interface = Some($interface.to_string());
-        let argument = Some($argument);
-
-        // Looking for default path or path specified by environment variable.
-        let xml_path = $crate::resolve_xml_path(None)
-            .unwrap_or_else(|err| panic!("Failed to resolve XML path: {err}"));
-
-        // Find the definition of the method in the XML specification.
-        let (file_path, interface_name) =
-            $crate::find_definition_in_dbus_xml!(xml_path, member, interface, MsgType::Method);
-
-        let file = std::fs::File::open(file_path).expect("Failed to open file");
-        $crate::get_method_return_type(file, &interface_name, member, argument)
-            .expect("Failed to get method argument(s) type signature")
+        let argument = $crate::ArgSelector::from($argument);
+        let shape: $crate::BodyShape = $shape;
+
+        let index = $crate::ProtocolIndex::shared()
+            .unwrap_or_else(|err| panic!("Failed to index XML definitions: {err}"));
+
+        // Find the definition of the member in the XML specification.
+        let definition = index
+            .locate(MsgType::Method, member, interface.as_deref())
+            .unwrap_or_else(|err| panic!("{err}"));
+
+        index
+            .method_return_type(definition.interface, member, argument, shape)
+            .unwrap_or_else(|err| {
+                panic!("Failed to get method argument(s) type signature from {definition}: {err}")
+            })
     }};
 
     (member: $member:expr, interface: $interface:expr, argument: $argument:expr) => {
         $crate::method_return_signature!($member, $interface, $argument)
     };
+
+    (member: $member:expr, interface: $interface:expr, argument: $argument:expr, shape: $shape:expr) => {
+        $crate::method_return_signature!($member, $interface, $argument, $shape)
+    };
 }
 
 /// Retrieve the signature of a method's arguments.
@@ -296,7 +247,8 @@ macro_rules! method_return_signature {
 /// assert_eq!(&sig, &Signature::from_str("(su)").expect("Valid signature pattern"));
 /// ```
 /// The macro supports colling arguments with identifiers as well as without.
-/// The macro may also be called with an interface name or interface and argument name:
+/// The macro may also be called with an interface name or interface and argument selector,
+/// which can be anything that converts into an [`ArgSelector`](crate::ArgSelector):
 ///
 /// ```rust
 /// # use zbus_lockstep::{method_args_signature};
@@ -305,6 +257,10 @@ macro_rules! method_return_signature {
 /// // or alternatively
 ///
 /// let _sig = method_args_signature!(member: "RequestName", interface: "org.example.Node", argument: "apple");
+///
+/// // or choose the shape of the signature, see [`BodyShape`](crate::BodyShape)
+///
+/// let _sig = method_args_signature!("RequestName", "org.example.Node", "apple", zbus_lockstep::BodyShape::Struct);
 /// ```
 #[macro_export]
 macro_rules! method_args_signature {
@@ -312,17 +268,28 @@ macro_rules! method_args_signature {
         use $crate::MsgType;
         let member = $member;
 
-        // Looking for default path or path specified by environment variable.
-        let xml_path = $crate::resolve_xml_path(None)
-            .unwrap_or_else(|err| panic!("Failed to resolve XML path: {err}"));
-
-        // Find the definition of the method in the XML specification.
-        let (file_path, interface_name) =
-            $crate::find_definition_in_dbus_xml!(xml_path, member, None, MsgType::Method);
-
-        let file = std::fs::File::open(file_path).expect("Failed to open file");
-        $crate::get_method_args_type(file, &interface_name, member, None)
-            .expect("Failed to get method arguments type signature")
+        let index = $crate::ProtocolIndex::shared()
+            .unwrap_or_else(|err| panic!("Failed to index XML definitions: {err}"));
+
+        // Find the definition of the member in the XML specification.
+        let definition = index
+            .locate(
+                MsgType::Method,
+                member,
+                $crate::LockstepConfig::default_interface(),
+            )
+            .unwrap_or_else(|err| panic!("{err}"));
+
+        index
+            .method_args_type(
+                definition.interface,
+                member,
+                None,
+                $crate::LockstepConfig::default_shape(),
+            )
+            .unwrap_or_else(|err| {
+                panic!("Failed to get method arguments type signature from {definition}: {err}")
+            })
     }};
 
     (member: $member:expr) => {
@@ -335,45 +302,69 @@ macro_rules! method_args_signature {
 
         let interface = Some($interface.to_string());
 
-        // Looking for default path or path specified by environment variable.
-        let xml_path = $crate::resolve_xml_path(None)
-            .unwrap_or_else(|err| panic!("Failed to resolve XML path: {err}"));
-
-        // Find the definition of the method in the XML specification.
-        let (file_path, interface_name) =
-            $crate::find_definition_in_dbus_xml!(xml_path, member, interface, MsgType::Method);
-
-        let file = std::fs::File::open(file_path).expect("Failed to open file");
-        $crate::get_method_args_type(file, &interface_name, member, None)
-            .expect("Failed to get method arguments type signature")
+        let index = $crate::ProtocolIndex::shared()
+            .unwrap_or_else(|err| panic!("Failed to index XML definitions: {err}"));
+
+        // Find the definition of the member in the XML specification.
+        let definition = index
+            .locate(MsgType::Method, member, interface.as_deref())
+            .unwrap_or_else(|err| panic!("{err}"));
+
+        index
+            .method_args_type(
+                definition.interface,
+                member,
+                None,
+                $crate::LockstepConfig::default_shape(),
+            )
+            .unwrap_or_else(|err| {
+                panic!("Failed to get method arguments type signature from {definition}: {err}")
+            })
     }};
 
     (member: $member:expr, interface: $interface:expr) => {
         $crate::method_args_signature!($member, $interface)
     };
 
-    ($member:expr, $interface:expr, $argument:expr) => {{
+    ($member:expr, $interface:expr, $argument:expr) => {
+        $crate::method_args_signature!(
+            $member,
+            $interface,
+            $argument,
+            $crate::LockstepConfig::default_shape()
+        )
+    };
+
+    ($member:expr, $interface:expr, $argument:expr, $shape:expr) => {{
         use $crate::MsgType;
         let member = $member;
         let interface = Some($interface.to_string());
 
-        let argument = Some($argument);
+        let argument = $crate::ArgSelector::from($argument);
+        let shape: $crate::BodyShape = $shape;
 
-        // Looking for default path or path specified by environment variable.
-        let xml_path = $crate::resolve_xml_path(None)
-            .unwrap_or_else(|err| panic!("Failed to resolve XML path: {err}"));
-        // Find the definition of the method in the XML specification.
-        let (file_path, interface_name) =
-            $crate::find_definition_in_dbus_xml!(xml_path, member, interface, MsgType::Method);
+        let index = $crate::ProtocolIndex::shared()
+            .unwrap_or_else(|err| panic!("Failed to index XML definitions: {err}"));
 
-        let file = std::fs::File::open(file_path).expect("Failed to open file");
-        $crate::get_method_args_type(file, &interface_name, member, argument)
-            .expect("Failed to get method argument(s) type signature")
+        // Find the definition of the member in the XML specification.
+        let definition = index
+            .locate(MsgType::Method, member, interface.as_deref())
+            .unwrap_or_else(|err| panic!("{err}"));
+
+        index
+            .method_args_type(definition.interface, member, argument, shape)
+            .unwrap_or_else(|err| {
+                panic!("Failed to get method argument(s) type signature from {definition}: {err}")
+            })
     }};
 
     (member: $member:expr, interface: $interface:expr, argument: $argument:expr) => {
         $crate::method_args_signature!($member, $interface, $argument)
     };
+
+    (member: $member:expr, interface: $interface:expr, argument: $argument:expr, shape: $shape:expr) => {
+        $crate::method_args_signature!($member, $interface, $argument, $shape)
+    };
 }
 
 /// Retrieve the signature of a signal's body type.
@@ -400,7 +391,8 @@ macro_rules! method_args_signature {
 /// assert_eq!(&sig, &Signature::from_str("(so)").expect("Valid signature pattern"));
 /// ```
 /// The macro supports colling arguments with identifiers as well as without.
-/// The macro may also be called with an interface name or interface and argument name:
+/// The macro may also be called with an interface name or interface and argument selector,
+/// which can be anything that converts into an [`ArgSelector`](crate::ArgSelector):
 ///
 /// ```rust
 /// # use zbus_lockstep::{signal_body_type_signature};
@@ -409,6 +401,14 @@ macro_rules! method_args_signature {
 /// // or alternatively
 ///
 /// let _sig = signal_body_type_signature!(member: "Alert", interface: "org.example.Node", argument: "color");
+///
+/// // or select arguments by position
+///
+/// let _sig = signal_body_type_signature!(member: "Alert", interface: "org.example.Node", argument: 1..3);
+///
+/// // or choose the shape of the signature, see [`BodyShape`](crate::BodyShape)
+///
+/// let _sig = signal_body_type_signature!("Alert", "org.example.Node", "color", zbus_lockstep::BodyShape::Struct);
 /// ```
 #[macro_export]
 macro_rules! signal_body_type_signature {
@@ -416,18 +416,28 @@ macro_rules! signal_body_type_signature {
         use $crate::MsgType;
         let member = $member;
 
-        // Looking for default path or path specified by environment variable.
-        let xml_path = $crate::resolve_xml_path(None)
-            .unwrap_or_else(|err| panic!("Failed to resolve XML path: {err}"));
-
-        // Find the definition of the method in the XML specification.
-        let (file_path, interface_name) =
-            $crate::find_definition_in_dbus_xml!(xml_path, member, None, MsgType::Signal);
-
-        let file = std::fs::File::open(file_path).expect("Failed to open file");
-
-        $crate::get_signal_body_type(file, &interface_name, member, None)
-            .expect("Failed to get method arguments type signature")
+        let index = $crate::ProtocolIndex::shared()
+            .unwrap_or_else(|err| panic!("Failed to index XML definitions: {err}"));
+
+        // Find the definition of the member in the XML specification.
+        let definition = index
+            .locate(
+                MsgType::Signal,
+                member,
+                $crate::LockstepConfig::default_interface(),
+            )
+            .unwrap_or_else(|err| panic!("{err}"));
+
+        index
+            .signal_body_type(
+                definition.interface,
+                member,
+                None,
+                $crate::LockstepConfig::default_shape(),
+            )
+            .unwrap_or_else(|err| {
+                panic!("Failed to get method arguments type signature from {definition}: {err}")
+            })
     }};
 
     (member: $member:expr) => {
@@ -439,46 +449,69 @@ macro_rules! signal_body_type_signature {
         let member = $member;
         let interface = Some($interface.to_string());
 
-        // Looking for default path or path specified by environment variable.
-        let xml_path = $crate::resolve_xml_path(None)
-            .unwrap_or_else(|err| panic!("Failed to resolve XML path: {err}"));
-
-        // Find the definition of the method in the XML specification.
-        let (file_path, interface_name) =
-            $crate::find_definition_in_dbus_xml!(xml_path, member, interface, MsgType::Signal);
-
-        let file = std::fs::File::open(file_path).expect("Failed to open file");
-        $crate::get_signal_body_type(file, &interface_name, member, None)
-            .expect("Failed to get method arguments type signature")
+        let index = $crate::ProtocolIndex::shared()
+            .unwrap_or_else(|err| panic!("Failed to index XML definitions: {err}"));
+
+        // Find the definition of the member in the XML specification.
+        let definition = index
+            .locate(MsgType::Signal, member, interface.as_deref())
+            .unwrap_or_else(|err| panic!("{err}"));
+
+        index
+            .signal_body_type(
+                definition.interface,
+                member,
+                None,
+                $crate::LockstepConfig::default_shape(),
+            )
+            .unwrap_or_else(|err| {
+                panic!("Failed to get method arguments type signature from {definition}: {err}")
+            })
     }};
 
     (member: $member:expr, interface: $interface:expr) => {
         $crate::signal_body_type_signature!($member, $interface)
     };
 
-    ($member:expr, $interface:expr, $argument:expr) => {{
+    ($member:expr, $interface:expr, $argument:expr) => {
+        $crate::signal_body_type_signature!(
+            $member,
+            $interface,
+            $argument,
+            $crate::LockstepConfig::default_shape()
+        )
+    };
+
+    ($member:expr, $interface:expr, $argument:expr, $shape:expr) => {{
         use $crate::MsgType;
         let member = $member;
         let interface = Some($interface.to_string());
 
-        let argument = Some($argument);
+        let argument = $crate::ArgSelector::from($argument);
+        let shape: $crate::BodyShape = $shape;
 
-        // Looking for default path or path specified by environment variable.
-        let xml_path = $crate::resolve_xml_path(None)
-            .unwrap_or_else(|err| panic!("Failed to resolve XML path: {err}"));
+        let index = $crate::ProtocolIndex::shared()
+            .unwrap_or_else(|err| panic!("Failed to index XML definitions: {err}"));
 
-        // Find the definition of the method in the XML specification.
-        let (file_path, interface_name) =
-            $crate::find_definition_in_dbus_xml!(xml_path, member, interface, MsgType::Signal);
+        // Find the definition of the member in the XML specification.
+        let definition = index
+            .locate(MsgType::Signal, member, interface.as_deref())
+            .unwrap_or_else(|err| panic!("{err}"));
 
-        let file = std::fs::File::open(file_path).expect("Failed to open file");
-        $crate::get_signal_body_type(file, &interface_name, member, argument)
-            .expect("Failed to get method argument(s) type signature")
+        index
+            .signal_body_type(definition.interface, member, argument, shape)
+            .unwrap_or_else(|err| {
+                panic!("Failed to get method argument(s) type signature from {definition}: {err}")
+            })
     }};
 
     (member: $member:expr, interface: $interface:expr, argument: $argument:expr) => {
         $crate::signal_body_type_signature!($member, $interface, $argument)
     };
+
+    (member: $member:expr, interface: $interface:expr, argument: $argument:expr, shape: $shape:expr) => {
+        $crate::signal_body_type_signature!($member, $interface, $argument, $shape)
+    };
 }
 
 /// Retrieve the signature of a property's type.
@@ -510,24 +543,41 @@ macro_rules! signal_body_type_signature {
 /// # use zbus_lockstep::{property_type_signature};
 /// let _sig = property_type_signature!(member: "Features", interface: "org.example.Node");
 /// ```
+///
+/// With `access`, the macro also asserts the property's access mode, and panics if it differs,
+/// e.g. when a read-only property turned writable:
+///
+/// ```rust
+/// # use zbus_lockstep::{property_type_signature, PropertyAccess};
+/// let _sig = property_type_signature!(member: "Features", access: PropertyAccess::Read);
+///
+/// // or alternatively
+///
+/// let _sig = property_type_signature!("Features", "org.example.Node", PropertyAccess::Read);
+/// ```
 #[macro_export]
 macro_rules! property_type_signature {
     ($member:expr) => {{
         use $crate::MsgType;
         let member = $member;
 
-        // Looking for default path or path specified by environment variable.
-        let xml_path = $crate::resolve_xml_path(None)
-            .unwrap_or_else(|err| panic!("Failed to resolve XML path: {err}"));
-
-        // Find the definition of the method in the XML specification.
-        let (file_path, interface_name) =
-            $crate::find_definition_in_dbus_xml!(xml_path, member, None, MsgType::Property);
-
-        let file = std::fs::File::open(file_path).expect("Failed to open file");
-
-        $crate::get_property_type(file, &interface_name, member)
-            .expect("Failed to get property type signature")
+        let index = $crate::ProtocolIndex::shared()
+            .unwrap_or_else(|err| panic!("Failed to index XML definitions: {err}"));
+
+        // Find the definition of the member in the XML specification.
+        let definition = index
+            .locate(
+                MsgType::Property,
+                member,
+                $crate::LockstepConfig::default_interface(),
+            )
+            .unwrap_or_else(|err| panic!("{err}"));
+
+        index
+            .property_type(definition.interface, member)
+            .unwrap_or_else(|err| {
+                panic!("Failed to get property type signature from {definition}: {err}")
+            })
     }};
 
     (member: $member:expr) => {
@@ -539,22 +589,147 @@ macro_rules! property_type_signature {
         let member = $member;
         let interface = Some($interface.to_string());
 
-        // Looking for default path or path specified by environment variable.
-        let xml_path = $crate::resolve_xml_path(None)
-            .unwrap_or_else(|err| panic!("Failed to resolve XML path: {err}"));
+        let index = $crate::ProtocolIndex::shared()
+            .unwrap_or_else(|err| panic!("Failed to index XML definitions: {err}"));
 
-        // Find the definition of the method in the XML specification.
-        let (file_path, interface_name) =
-            $crate::find_definition_in_dbus_xml!(xml_path, member, interface, MsgType::Property);
+        // Find the definition of the member in the XML specification.
+        let definition = index
+            .locate(MsgType::Property, member, interface.as_deref())
+            .unwrap_or_else(|err| panic!("{err}"));
 
-        let file = std::fs::File::open(file_path).expect("Failed to open file");
-        $crate::get_property_type(file, &interface_name, member)
-            .expect("Failed to get property type signature")
+        index
+            .property_type(definition.interface, member)
+            .unwrap_or_else(|err| {
+                panic!("Failed to get property type signature from {definition}: {err}")
+            })
     }};
 
     (member: $member:expr, interface: $interface:expr) => {
         $crate::property_type_signature!($member, $interface)
     };
+
+    ($member:expr, $interface:expr, $access:expr) => {{
+        use $crate::MsgType;
+        let member = $member;
+        let interface = Some($interface.to_string());
+        let access: $crate::PropertyAccess = $access;
+
+        let index = $crate::ProtocolIndex::shared()
+            .unwrap_or_else(|err| panic!("Failed to index XML definitions: {err}"));
+
+        // Find the definition of the member in the XML specification.
+        let definition = index
+            .locate(MsgType::Property, member, interface.as_deref())
+            .unwrap_or_else(|err| panic!("{err}"));
+
+        let property = index
+            .property(definition.interface, member)
+            .unwrap_or_else(|err| {
+                panic!("Failed to get property type signature from {definition}: {err}")
+            });
+        property
+            .expect_access(access)
+            .unwrap_or_else(|err| panic!("{err}"));
+
+        property.signature
+    }};
+
+    (member: $member:expr, interface: $interface:expr, access: $access:expr) => {
+        $crate::property_type_signature!($member, $interface, $access)
+    };
+
+    (member: $member:expr, access: $access:expr) => {{
+        use $crate::MsgType;
+        let member = $member;
+        let access: $crate::PropertyAccess = $access;
+
+        let index = $crate::ProtocolIndex::shared()
+            .unwrap_or_else(|err| panic!("Failed to index XML definitions: {err}"));
+
+        // Find the definition of the member in the XML specification.
+        let definition = index
+            .locate(
+                MsgType::Property,
+                member,
+                $crate::LockstepConfig::default_interface(),
+            )
+            .unwrap_or_else(|err| panic!("{err}"));
+
+        let property = index
+            .property(definition.interface, member)
+            .unwrap_or_else(|err| {
+                panic!("Failed to get property type signature from {definition}: {err}")
+            });
+        property
+            .expect_access(access)
+            .unwrap_or_else(|err| panic!("{err}"));
+
+        property.signature
+    }};
+}
+
+/// Assert that two signatures are equal, reporting where they differ if they are not.
+///
+/// Unlike `assert_eq!`, which prints both signatures in full, the panic message lists each
+/// divergence with its path, see [`SignatureDiff`](crate::SignatureDiff). The optional third
+/// argument names the fields of the outermost structure. The optional fourth argument is a
+/// [`SignaturePolicy`](crate::SignaturePolicy) for differences to accept, which defaults to the
+/// configured one, see [`LockstepConfig::default_policy`](crate::LockstepConfig::default_policy).
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::{assert_signature_eq, signal_body_type_signature};
+/// use zvariant::{OwnedObjectPath, Type};
+///
+/// #[derive(Type)]
+/// struct Node {
+///     name: String,
+///     path: OwnedObjectPath,
+/// }
+///
+/// let signature = signal_body_type_signature!("AddNode");
+/// assert_signature_eq!(signature, Node::SIGNATURE);
+/// assert_signature_eq!(signature, Node::SIGNATURE, &["name", "path"]);
+///
+/// // A `u32` where the XML says `i`.
+/// #[derive(Type)]
+/// #[repr(u32)]
+/// enum Level {
+///     Low,
+///     High,
+/// }
+///
+/// let signature = signal_body_type_signature!("Level");
+/// assert_signature_eq!(
+///     signature,
+///     Level::SIGNATURE,
+///     &[],
+///     zbus_lockstep::SignaturePolicy::AllowSignDifference
+/// );
+/// ```
+#[macro_export]
+macro_rules! assert_signature_eq {
+    ($expected:expr, $found:expr $(,)?) => {
+        $crate::assert_signature_eq!($expected, $found, &[])
+    };
+
+    ($expected:expr, $found:expr, $field_names:expr $(,)?) => {
+        $crate::assert_signature_eq!(
+            $expected,
+            $found,
+            $field_names,
+            $crate::LockstepConfig::default_policy()
+        )
+    };
+
+    ($expected:expr, $found:expr, $field_names:expr, $policy:expr $(,)?) => {{
+        let policy: $crate::SignaturePolicy = $policy;
+        let diff = $crate::SignatureDiff::with_policy(&$expected, &$found, $field_names, &policy);
+        if !diff.is_empty() {
+            panic!("{diff}");
+        }
+    }};
 }
 
 #[cfg(test)]
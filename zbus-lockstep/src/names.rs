@@ -0,0 +1,266 @@
+//! Comparison of XML argument names with Rust field names.
+
+use std::fmt;
+
+/// A way in which the fields of a struct do not follow the arguments of a member.
+///
+/// Positions count from zero.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NameMismatch {
+    /// The field at `position` has a different name than the argument there.
+    Renamed {
+        position: usize,
+        arg: String,
+        field: String,
+    },
+    /// The argument `name` has a field, but at another position.
+    Reordered {
+        name: String,
+        arg_position: usize,
+        field_position: usize,
+    },
+    /// The argument at `position` has no field.
+    Missing { position: usize, arg: String },
+    /// The field at `position` has no argument.
+    Unexpected { position: usize, field: String },
+}
+
+impl fmt::Display for NameMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NameMismatch::Renamed {
+                position,
+                arg,
+                field,
+            } => write!(
+                f,
+                "field {position} is named `{field}`, but the argument is named `{arg}`"
+            ),
+            NameMismatch::Reordered {
+                name,
+                arg_position,
+                field_position,
+            } => write!(
+                f,
+                "field `{name}` is at position {field_position}, but the argument is at position {arg_position}"
+            ),
+            NameMismatch::Missing { position, arg } => {
+                write!(f, "argument {position} (`{arg}`) has no field")
+            }
+            NameMismatch::Unexpected { position, field } => {
+                write!(f, "field {position} (`{field}`) has no argument")
+            }
+        }
+    }
+}
+
+/// Compare the names of a member's arguments with the field names of a struct, in order.
+///
+/// Argument names are converted to snake case first, so that `nodeAdded` matches `node_added`.
+/// Leading underscores of field names are ignored, so that `_node_added` matches as well.
+/// Arguments without a name cannot be compared and are skipped.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::{NameMismatch, compare_arg_names};
+///
+/// let arg_names = [Some("name".to_string()), Some("path".to_string())];
+///
+/// assert!(compare_arg_names(&arg_names, &["name", "path"]).is_empty());
+/// assert_eq!(
+///     compare_arg_names(&arg_names, &["path", "name"])[0],
+///     NameMismatch::Reordered {
+///         name: "name".to_string(),
+///         arg_position: 0,
+///         field_position: 1,
+///     }
+/// );
+/// ```
+#[must_use]
+pub fn compare_arg_names(arg_names: &[Option<String>], field_names: &[&str]) -> Vec<NameMismatch> {
+    let args: Vec<Option<String>> = arg_names
+        .iter()
+        .map(|name| name.as_deref().map(snake_case))
+        .collect();
+    let fields: Vec<&str> = field_names
+        .iter()
+        .map(|name| name.trim_start_matches('_'))
+        .collect();
+
+    let field_position = |name: &str| fields.iter().position(|field| *field == name);
+    let is_arg = |name: &str| args.iter().any(|arg| arg.as_deref() == Some(name));
+
+    let mut mismatches = Vec::new();
+    // The fields accounted for by a `Renamed` mismatch, or by an unnamed argument.
+    let mut compared = vec![false; fields.len()];
+    for position in 0..args.len() {
+        match (&args[position], fields.get(position)) {
+            (Some(arg), Some(field)) if arg == field => {}
+            (Some(arg), field) => {
+                if let Some(field_position) = field_position(arg) {
+                    mismatches.push(NameMismatch::Reordered {
+                        name: arg.clone(),
+                        arg_position: position,
+                        field_position,
+                    });
+                } else if let Some(field) = field.filter(|field| !is_arg(field)) {
+                    compared[position] = true;
+                    mismatches.push(NameMismatch::Renamed {
+                        position,
+                        arg: arg.clone(),
+                        field: (*field).to_owned(),
+                    });
+                } else {
+                    mismatches.push(NameMismatch::Missing {
+                        position,
+                        arg: arg.clone(),
+                    });
+                }
+            }
+            (None, Some(_)) => compared[position] = true,
+            (None, None) => {}
+        }
+    }
+
+    // Fields of another argument's name are reported as reordered with that argument.
+    for (position, field) in fields.iter().enumerate() {
+        if !compared[position] && !is_arg(field) {
+            mismatches.push(NameMismatch::Unexpected {
+                position,
+                field: (*field).to_owned(),
+            });
+        }
+    }
+
+    mismatches
+}
+
+/// Convert a `camelCase`, `PascalCase` or `kebab-case` name to `snake_case`.
+pub(crate) fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut snake = String::with_capacity(name.len() + 4);
+
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '-' {
+            snake.push('_');
+        } else if c.is_uppercase() {
+            let prev = i.checked_sub(1).map(|i| chars[i]);
+            let next = chars.get(i + 1);
+            // A word starts at an uppercase letter after a lowercase letter or digit, or at the
+            // last capital of an acronym, such as the `P` in `XMLPath`.
+            let starts_word = prev.is_some_and(|p| p.is_lowercase() || p.is_ascii_digit())
+                || (prev.is_some_and(char::is_uppercase) && next.is_some_and(|n| n.is_lowercase()));
+            if starts_word && !snake.ends_with('_') {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+
+    snake
+}
+
+#[cfg(test)]
+mod test {
+    use super::{NameMismatch, compare_arg_names, snake_case};
+
+    fn names(names: &[&str]) -> Vec<Option<String>> {
+        names.iter().map(|name| Some((*name).to_owned())).collect()
+    }
+
+    #[test]
+    fn test_snake_case() {
+        assert_eq!(snake_case("nodeAdded"), "node_added");
+        assert_eq!(snake_case("NodeAdded"), "node_added");
+        assert_eq!(snake_case("app_name"), "app_name");
+        assert_eq!(snake_case("XMLPath"), "xml_path");
+        assert_eq!(snake_case("index2Parent"), "index2_parent");
+        assert_eq!(snake_case("state-set"), "state_set");
+    }
+
+    #[test]
+    fn test_compare_arg_names_normalizes_names() {
+        let args = names(&["nodeAdded", "childCount"]);
+        assert!(compare_arg_names(&args, &["node_added", "_child_count"]).is_empty());
+    }
+
+    #[test]
+    fn test_compare_arg_names_reports_swapped_fields() {
+        let args = names(&["name", "path"]);
+        assert_eq!(
+            compare_arg_names(&args, &["path", "name"]),
+            [
+                NameMismatch::Reordered {
+                    name: "name".to_owned(),
+                    arg_position: 0,
+                    field_position: 1,
+                },
+                NameMismatch::Reordered {
+                    name: "path".to_owned(),
+                    arg_position: 1,
+                    field_position: 0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compare_arg_names_reports_renamed_missing_and_unexpected_fields() {
+        let args = names(&["urgent", "color", "volume"]);
+        assert_eq!(
+            compare_arg_names(&args, &["urgent", "colour"]),
+            [
+                NameMismatch::Renamed {
+                    position: 1,
+                    arg: "color".to_owned(),
+                    field: "colour".to_owned(),
+                },
+                NameMismatch::Missing {
+                    position: 2,
+                    arg: "volume".to_owned(),
+                },
+            ]
+        );
+
+        let args = names(&["urgent"]);
+        assert_eq!(
+            compare_arg_names(&args, &["urgent", "color"]),
+            [NameMismatch::Unexpected {
+                position: 1,
+                field: "color".to_owned(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_compare_arg_names_reports_unexpected_fields_among_reordered_ones() {
+        let args = names(&["name", "path"]);
+        assert_eq!(
+            compare_arg_names(&args, &["path", "extra"]),
+            [
+                NameMismatch::Missing {
+                    position: 0,
+                    arg: "name".to_owned(),
+                },
+                NameMismatch::Reordered {
+                    name: "path".to_owned(),
+                    arg_position: 1,
+                    field_position: 0,
+                },
+                NameMismatch::Unexpected {
+                    position: 1,
+                    field: "extra".to_owned(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compare_arg_names_skips_unnamed_arguments() {
+        let args = vec![None, Some("volume".to_owned())];
+        assert!(compare_arg_names(&args, &["anything", "volume"]).is_empty());
+    }
+}
@@ -0,0 +1,127 @@
+//! Relaxations of signature equality.
+
+use std::{fmt, sync::Arc};
+
+use zvariant::Signature;
+
+// A custom rule, called with the expected and the found signature.
+type Rule = Arc<dyn Fn(&Signature, &Signature) -> bool + Send + Sync>;
+
+/// Which differences between an expected and a found signature are acceptable.
+///
+/// Some Rust representations deliberately differ from the XML: a `#[repr(u32)]` enum for an `i`,
+/// or a `zvariant::Value` for a concrete `s`. A policy accepts such differences wherever they
+/// occur in a signature, see [`SignatureDiff::with_policy`](crate::SignatureDiff::with_policy).
+///
+/// # Examples
+///
+/// ```rust
+/// use std::str::FromStr;
+/// use zbus_lockstep::SignaturePolicy;
+/// use zvariant::Signature;
+///
+/// let i = Signature::from_str("i").unwrap();
+/// let u = Signature::from_str("u").unwrap();
+/// let v = Signature::from_str("v").unwrap();
+///
+/// assert!(!SignaturePolicy::Exact.accepts(&i, &u));
+/// assert!(SignaturePolicy::AllowSignDifference.accepts(&i, &u));
+///
+/// let policy = SignaturePolicy::AllowSignDifference.or(SignaturePolicy::AllowVariantWidening);
+/// assert!(policy.accepts(&i, &v));
+///
+/// // Accept a `u` for a `b`, with a custom rule.
+/// let policy = SignaturePolicy::custom("bool as u32", |expected, found| {
+///     *expected == Signature::Bool && *found == Signature::U32
+/// });
+/// assert!(policy.accepts(&Signature::Bool, &u));
+/// ```
+#[derive(Clone, Default)]
+pub enum SignaturePolicy {
+    /// Only equal signatures are accepted.
+    #[default]
+    Exact,
+    /// A variant, `v`, is accepted in place of any type.
+    AllowVariantWidening,
+    /// An integer is accepted in place of the integer of the same width with the other
+    /// signedness: `i` and `u`, `n` and `q`, `x` and `t`.
+    AllowSignDifference,
+    /// A named rule, called with the expected and the found signature where they differ.
+    Custom { name: String, rule: Rule },
+    /// Whatever any of the policies accepts.
+    Any(Vec<SignaturePolicy>),
+}
+
+impl SignaturePolicy {
+    /// A policy that accepts a difference when `rule` returns `true` for the expected and the
+    /// found signature. `name` describes the relaxation in reports.
+    pub fn custom(
+        name: impl Into<String>,
+        rule: impl Fn(&Signature, &Signature) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        SignaturePolicy::Custom {
+            name: name.into(),
+            rule: Arc::new(rule),
+        }
+    }
+
+    /// A policy that accepts whatever either `self` or `other` accepts, and so is at least as
+    /// lenient as each of them.
+    #[must_use]
+    pub fn or(self, other: SignaturePolicy) -> Self {
+        match self {
+            SignaturePolicy::Any(mut policies) => {
+                policies.push(other);
+                SignaturePolicy::Any(policies)
+            }
+            policy => SignaturePolicy::Any(vec![policy, other]),
+        }
+    }
+
+    /// Whether `found` is acceptable where `expected` is required.
+    #[must_use]
+    pub fn accepts(&self, expected: &Signature, found: &Signature) -> bool {
+        expected == found || self.relaxation(expected, found).is_some()
+    }
+
+    /// The name of the relaxation that accepts `found` in place of a different `expected`, if any.
+    #[must_use]
+    pub fn relaxation(&self, expected: &Signature, found: &Signature) -> Option<&str> {
+        match self {
+            SignaturePolicy::Exact => None,
+            SignaturePolicy::AllowVariantWidening => (*found == Signature::Variant
+                && *expected != Signature::Unit)
+                .then_some("variant widening"),
+            SignaturePolicy::AllowSignDifference => {
+                let signed_unsigned = |a: &Signature, b: &Signature| {
+                    matches!(
+                        (a, b),
+                        (Signature::I16, Signature::U16)
+                            | (Signature::I32, Signature::U32)
+                            | (Signature::I64, Signature::U64)
+                    )
+                };
+                (signed_unsigned(expected, found) || signed_unsigned(found, expected))
+                    .then_some("sign difference")
+            }
+            SignaturePolicy::Custom { name, rule } => {
+                rule(expected, found).then_some(name.as_str())
+            }
+            SignaturePolicy::Any(policies) => policies
+                .iter()
+                .find_map(|policy| policy.relaxation(expected, found)),
+        }
+    }
+}
+
+impl fmt::Debug for SignaturePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignaturePolicy::Exact => write!(f, "Exact"),
+            SignaturePolicy::AllowVariantWidening => write!(f, "AllowVariantWidening"),
+            SignaturePolicy::AllowSignDifference => write!(f, "AllowSignDifference"),
+            SignaturePolicy::Custom { name, .. } => f.debug_tuple("Custom").field(name).finish(),
+            SignaturePolicy::Any(policies) => f.debug_tuple("Any").field(policies).finish(),
+        }
+    }
+}
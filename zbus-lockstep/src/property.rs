@@ -0,0 +1,61 @@
+//! The full description of a property.
+
+use zbus_xml::PropertyAccess;
+use zvariant::Signature;
+
+use crate::{Annotations, LockstepError::PropertyAccessMismatch, Result};
+
+/// A property as described in XML: its type, access mode and annotations.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::{PropertyAccess, get_property};
+///
+/// let xml = r#"
+/// <node>
+///   <interface name="org.example.Node">
+///     <property name="Features" type="as" access="read"/>
+///   </interface>
+/// </node>
+/// "#;
+///
+/// let property = get_property(xml.as_bytes(), "org.example.Node", "Features").unwrap();
+/// assert_eq!(property.signature, "as");
+/// assert_eq!(property.access, PropertyAccess::Read);
+///
+/// // A setter would be a mistake.
+/// assert!(property.expect_access(PropertyAccess::ReadWrite).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PropertyDescriptor {
+    /// The name of the property.
+    pub name: String,
+
+    /// The signature of the property's type.
+    pub signature: Signature,
+
+    /// Whether the property can be read, written or both.
+    pub access: PropertyAccess,
+
+    /// The annotations of the property.
+    pub annotations: Annotations,
+}
+
+impl PropertyDescriptor {
+    /// Check that the property has exactly the `expected` access mode.
+    ///
+    /// Returns [`LockstepError::PropertyAccessMismatch`](crate::LockstepError::PropertyAccessMismatch)
+    /// otherwise, e.g. when a read-only property turned writable.
+    pub fn expect_access(&self, expected: PropertyAccess) -> Result<()> {
+        if self.access == expected {
+            return Ok(());
+        }
+
+        Err(PropertyAccessMismatch {
+            name: self.name.clone(),
+            expected,
+            actual: self.access,
+        })
+    }
+}
@@ -0,0 +1,206 @@
+//! Selection of the arguments of a member whose signature is wanted.
+
+use std::ops::{Range, RangeFrom, RangeInclusive};
+
+use zbus_xml::{Arg, ArgDirection};
+
+use crate::{
+    LockstepError::{
+        ArgumentDirection, ArgumentIndexOutOfRange, ArgumentNotFound, ArgumentRangeOutOfRange,
+    },
+    Result,
+};
+
+/// Selects which arguments of a method or signal make up the signature of interest.
+///
+/// Positions count from zero. For methods, only the arguments of the relevant direction can be
+/// selected, e.g. the `out` arguments for the return type, and positions count only those.
+///
+/// `ArgSelector` converts from the values one would naturally write, so that the lookup functions
+/// and macros accept those directly:
+///
+/// ```rust
+/// use zbus_lockstep::ArgSelector;
+///
+/// assert_eq!(ArgSelector::from(None), ArgSelector::All);
+/// assert_eq!(ArgSelector::from("volume"), ArgSelector::Name("volume".to_string()));
+/// assert_eq!(ArgSelector::from(2), ArgSelector::Index(2));
+/// assert_eq!(ArgSelector::from(1..3), ArgSelector::Range(1..3));
+/// assert_eq!(ArgSelector::from(1..), ArgSelector::RangeFrom(1));
+/// assert_eq!(
+///     ArgSelector::from(["urgent", "color"]),
+///     ArgSelector::Names(vec!["urgent".to_string(), "color".to_string()])
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum ArgSelector {
+    /// All arguments.
+    #[default]
+    All,
+    /// The argument with this name.
+    Name(String),
+    /// The argument at this position.
+    Index(usize),
+    /// The arguments in this range of positions.
+    Range(Range<usize>),
+    /// The arguments from this position on.
+    RangeFrom(usize),
+    /// The arguments with these names, in the order given.
+    Names(Vec<String>),
+}
+
+impl ArgSelector {
+    /// Select from `selectable`, the arguments of `args` that have the relevant direction.
+    ///
+    /// `direction` is that direction for method arguments, and `None` for signal arguments.
+    /// `interface_name` and `member_name` name the member in errors.
+    pub(crate) fn select<'a>(
+        &self,
+        interface_name: &str,
+        member_name: &str,
+        args: &'a [Arg],
+        selectable: &[&'a Arg],
+        direction: Option<ArgDirection>,
+    ) -> Result<Vec<&'a Arg>> {
+        let by_name = |name: &str| {
+            if let Some(arg) = selectable.iter().find(|arg| arg.name() == Some(name)) {
+                return Ok(*arg);
+            }
+
+            // An argument by this name of the other direction is most likely a mistake, say so.
+            if let (Some(expected), Some(arg)) =
+                (direction, args.iter().find(|arg| arg.name() == Some(name)))
+            {
+                return Err(ArgumentDirection {
+                    name: name.to_owned(),
+                    expected,
+                    actual: method_arg_direction(arg),
+                });
+            }
+
+            Err(ArgumentNotFound {
+                name: name.to_owned(),
+                available: selectable
+                    .iter()
+                    .filter_map(|arg| arg.name().map(ToOwned::to_owned))
+                    .collect(),
+            })
+        };
+
+        match self {
+            ArgSelector::All => Ok(selectable.to_vec()),
+            ArgSelector::Name(name) => Ok(vec![by_name(name)?]),
+            ArgSelector::Names(names) => names.iter().map(|name| by_name(name)).collect(),
+            ArgSelector::Index(index) => {
+                selectable.get(*index).map(|arg| vec![*arg]).ok_or_else(|| {
+                    ArgumentIndexOutOfRange {
+                        interface: interface_name.to_owned(),
+                        member: member_name.to_owned(),
+                        index: *index,
+                        count: selectable.len(),
+                    }
+                })
+            }
+            ArgSelector::Range(range) => selectable
+                .get(range.clone())
+                .map(<[_]>::to_vec)
+                .ok_or_else(|| ArgumentRangeOutOfRange {
+                    interface: interface_name.to_owned(),
+                    member: member_name.to_owned(),
+                    range: range.clone(),
+                    count: selectable.len(),
+                }),
+            ArgSelector::RangeFrom(start) => selectable
+                .get(*start..)
+                .map(<[_]>::to_vec)
+                .ok_or_else(|| ArgumentIndexOutOfRange {
+                    interface: interface_name.to_owned(),
+                    member: member_name.to_owned(),
+                    index: *start,
+                    count: selectable.len(),
+                }),
+        }
+    }
+
+    /// A description of the selection for use in error messages, `None` if all arguments are
+    /// selected.
+    pub(crate) fn describe(&self) -> Option<String> {
+        match self {
+            ArgSelector::All => None,
+            ArgSelector::Name(name) => Some(name.clone()),
+            ArgSelector::Index(index) => Some(format!("#{index}")),
+            ArgSelector::Range(range) => Some(format!("#{}..#{}", range.start, range.end)),
+            ArgSelector::RangeFrom(start) => Some(format!("#{start}..")),
+            ArgSelector::Names(names) => Some(names.join(", ")),
+        }
+    }
+}
+
+/// The direction of a method argument, which is `in` if the XML does not say.
+pub(crate) fn method_arg_direction(arg: &Arg) -> ArgDirection {
+    arg.direction().unwrap_or(ArgDirection::In)
+}
+
+impl From<Option<&str>> for ArgSelector {
+    fn from(arg: Option<&str>) -> Self {
+        arg.map_or(ArgSelector::All, ArgSelector::from)
+    }
+}
+
+impl From<&str> for ArgSelector {
+    fn from(name: &str) -> Self {
+        ArgSelector::Name(name.to_owned())
+    }
+}
+
+impl From<String> for ArgSelector {
+    fn from(name: String) -> Self {
+        ArgSelector::Name(name)
+    }
+}
+
+impl From<usize> for ArgSelector {
+    fn from(index: usize) -> Self {
+        ArgSelector::Index(index)
+    }
+}
+
+impl From<Range<usize>> for ArgSelector {
+    fn from(range: Range<usize>) -> Self {
+        ArgSelector::Range(range)
+    }
+}
+
+impl From<RangeFrom<usize>> for ArgSelector {
+    fn from(range: RangeFrom<usize>) -> Self {
+        ArgSelector::RangeFrom(range.start)
+    }
+}
+
+impl From<RangeInclusive<usize>> for ArgSelector {
+    fn from(range: RangeInclusive<usize>) -> Self {
+        // A range up to and including `usize::MAX` has no exclusive end.
+        match range.end().checked_add(1) {
+            Some(end) => ArgSelector::Range(*range.start()..end),
+            None => ArgSelector::RangeFrom(*range.start()),
+        }
+    }
+}
+
+impl From<&[&str]> for ArgSelector {
+    fn from(names: &[&str]) -> Self {
+        ArgSelector::Names(names.iter().map(|name| (*name).to_owned()).collect())
+    }
+}
+
+impl<const N: usize> From<[&str; N]> for ArgSelector {
+    fn from(names: [&str; N]) -> Self {
+        ArgSelector::from(names.as_slice())
+    }
+}
+
+impl From<Vec<&str>> for ArgSelector {
+    fn from(names: Vec<&str>) -> Self {
+        ArgSelector::from(names.as_slice())
+    }
+}
@@ -0,0 +1,95 @@
+//! The shape in which a member's arguments are combined into a single signature.
+
+/// How the selected arguments of a method or signal are combined into one signature.
+///
+/// A `#[derive(Type)]` struct always has a structure signature, even if it has a single field,
+/// whereas a newtype or a plain value has the signature of the value itself. The shape decides
+/// which of the two the XML arguments are presented as.
+///
+/// | Arguments        | `Flattened` | `Struct`   |
+/// |------------------|-------------|------------|
+/// | `o`              | `o`         | `(o)`      |
+/// | `(so)`           | `(so)`      | `((so))`   |
+/// | `s`, `u`         | `(su)`      | `(su)`     |
+///
+/// Note that `zvariant` represents a sequence of several types as a structure, so for multiple
+/// arguments both shapes coincide.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::{BodyShape, get_signal_body_type_with_shape};
+/// use zvariant::{OwnedObjectPath, Type};
+///
+/// let xml = r#"
+/// <node>
+///   <interface name="org.freedesktop.bolt1.Manager">
+///     <signal name="DeviceAdded">
+///       <arg name="device" type="o"/>
+///     </signal>
+///   </interface>
+/// </node>
+/// "#;
+///
+/// #[derive(Type)]
+/// struct DeviceEvent {
+///     device: OwnedObjectPath,
+/// }
+///
+/// let interface = "org.freedesktop.bolt1.Manager";
+/// let signature = get_signal_body_type_with_shape(
+///     xml.as_bytes(),
+///     interface,
+///     "DeviceAdded",
+///     None,
+///     BodyShape::Struct,
+/// )
+/// .unwrap();
+/// assert_eq!(&signature, DeviceEvent::SIGNATURE);
+///
+/// let signature = get_signal_body_type_with_shape(
+///     xml.as_bytes(),
+///     interface,
+///     "DeviceAdded",
+///     None,
+///     BodyShape::Flattened,
+/// )
+/// .unwrap();
+/// assert_eq!(&signature, OwnedObjectPath::SIGNATURE);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum BodyShape {
+    /// The arguments as they are: a single argument has its own signature.
+    Flattened,
+    /// The arguments wrapped in a structure, as a `#[derive(Type)]` struct with one field per
+    /// argument.
+    Struct,
+    /// Flattened when looking up a signature, while comparisons accept either shape.
+    #[default]
+    Auto,
+}
+
+impl BodyShape {
+    /// The concrete shapes a signature may have under this shape.
+    ///
+    /// `Auto` accepts both `Flattened` and `Struct`; the others accept only themselves.
+    #[must_use]
+    pub fn candidates(self) -> &'static [BodyShape] {
+        match self {
+            BodyShape::Flattened => &[BodyShape::Flattened],
+            BodyShape::Struct => &[BodyShape::Struct],
+            BodyShape::Auto => &[BodyShape::Flattened, BodyShape::Struct],
+        }
+    }
+
+    /// Combine the signatures of `args` according to this shape.
+    pub(crate) fn combine<'a>(self, args: impl IntoIterator<Item = &'a str>) -> String {
+        let mut count = 0;
+        let signature: String = args.into_iter().inspect(|_| count += 1).collect();
+
+        match self {
+            BodyShape::Struct if count > 0 => format!("({signature})"),
+            _ => signature,
+        }
+    }
+}
@@ -0,0 +1,279 @@
+// Tests for `ProtocolIndex`.
+
+use std::path::PathBuf;
+
+use zbus_lockstep::{
+    BodyShape, EmitsChangedSignal, LockstepError, MsgType, PropertyAccess, ProtocolIndex, XmlFilter,
+};
+
+const NODE_XML: &str = r#"
+<node>
+  <interface name="org.example.Node">
+    <signal name="AddNode">
+      <arg name="nodeAdded" type="(so)"/>
+    </signal>
+  </interface>
+</node>
+"#;
+
+const OTHER_NODE_XML: &str = r#"
+<node>
+  <interface name="org.example.OtherNode">
+    <signal name="AddNode">
+      <arg name="nodeAdded" type="(ss)"/>
+    </signal>
+  </interface>
+</node>
+"#;
+
+#[test]
+fn test_index_from_dir_answers_all_queries() {
+    let xml_path = PathBuf::from("../xml");
+    let index = ProtocolIndex::from_dir(&xml_path).unwrap();
+
+    let definition = index.locate(MsgType::Method, "RequestName", None).unwrap();
+    assert_eq!(definition.interface, "org.example.Node");
+    assert_eq!(
+        definition.path.unwrap(),
+        xml_path.join("test_definition_file.xml")
+    );
+
+    let iface = definition.interface;
+    assert_eq!(
+        index
+            .method_args_type(iface, "RequestName", None, BodyShape::Auto)
+            .unwrap(),
+        "su"
+    );
+    assert_eq!(
+        index
+            .method_return_type(iface, "RequestName", None, BodyShape::Auto)
+            .unwrap(),
+        "u"
+    );
+    assert_eq!(
+        index
+            .signal_body_type(iface, "Alert", Some("volume"), BodyShape::Auto)
+            .unwrap(),
+        "d"
+    );
+    assert_eq!(index.property_type(iface, "Features").unwrap(), "as");
+}
+
+#[test]
+fn test_index_reports_ambiguous_members() {
+    let index = ProtocolIndex::from_strs([NODE_XML, OTHER_NODE_XML]).unwrap();
+
+    let err = index
+        .locate(MsgType::Signal, "AddNode", None)
+        .expect_err("Two interfaces offer `AddNode`");
+    assert!(matches!(
+        err,
+        LockstepError::Ambiguous { candidates, .. }
+            if candidates == ["org.example.Node", "org.example.OtherNode"]
+    ));
+
+    let definition = index
+        .locate(MsgType::Signal, "AddNode", Some("org.example.OtherNode"))
+        .unwrap();
+    assert_eq!(definition.interface, "org.example.OtherNode");
+    assert_eq!(definition.path, None);
+}
+
+#[test]
+fn test_index_suggests_close_names() {
+    let index = ProtocolIndex::from_strs([NODE_XML]).unwrap();
+
+    let err = index
+        .locate(MsgType::Signal, "AddNode", Some("org.example.Nod"))
+        .expect_err("Interface does not exist");
+    assert_eq!(err.suggestions(), vec!["org.example.Node"]);
+
+    let err = index
+        .locate(MsgType::Signal, "AddNodes", None)
+        .expect_err("Signal does not exist");
+    assert_eq!(err.suggestions(), vec!["AddNode"]);
+}
+
+#[test]
+fn test_shared_index_is_built_once() {
+    let first = ProtocolIndex::shared().unwrap();
+    let second = ProtocolIndex::shared().unwrap();
+    assert!(std::ptr::eq(first, second));
+    assert!(
+        first
+            .interface_names()
+            .any(|name| name == "org.example.Node")
+    );
+}
+
+#[test]
+fn test_index_answers_annotation_queries() {
+    let index = ProtocolIndex::from_strs([r#"
+        <node>
+          <interface name="org.example.Annotated">
+            <annotation name="org.freedesktop.DBus.Property.EmitsChangedSignal" value="false"/>
+            <method name="Poke">
+              <arg name="strength" direction="in" type="u">
+                <annotation name="org.example.Unit" value="newton"/>
+              </arg>
+              <annotation name="org.freedesktop.DBus.Method.NoReply" value="true"/>
+            </method>
+            <property name="Size" type="u" access="read">
+              <annotation name="org.freedesktop.DBus.Property.EmitsChangedSignal" value="invalidates"/>
+            </property>
+            <signal name="Poked">
+              <annotation name="org.freedesktop.DBus.Deprecated" value="true"/>
+            </signal>
+          </interface>
+        </node>
+    "#])
+    .unwrap();
+    let iface = "org.example.Annotated";
+
+    let annotations = index.interface_annotations(iface).unwrap();
+    assert_eq!(
+        annotations.emits_changed_signal(),
+        Some(EmitsChangedSignal::False)
+    );
+
+    let annotations = index
+        .member_annotations(iface, MsgType::Method, "Poke")
+        .unwrap();
+    assert!(annotations.no_reply());
+    assert!(!annotations.deprecated());
+
+    let annotations = index
+        .member_annotations(iface, MsgType::Property, "Size")
+        .unwrap();
+    assert_eq!(
+        annotations.emits_changed_signal(),
+        Some(EmitsChangedSignal::Invalidates)
+    );
+
+    let annotations = index
+        .member_annotations(iface, MsgType::Signal, "Poked")
+        .unwrap();
+    assert!(annotations.deprecated());
+    assert_eq!(
+        annotations.iter().collect::<Vec<_>>(),
+        [("org.freedesktop.DBus.Deprecated", "true")]
+    );
+
+    let annotations = index
+        .arg_annotations(iface, MsgType::Method, "Poke", "strength")
+        .unwrap();
+    assert_eq!(annotations.len(), 1);
+    assert_eq!(annotations[0].get("org.example.Unit"), Some("newton"));
+
+    let property = index.property(iface, "Size").unwrap();
+    assert_eq!(property.signature, "u");
+    assert_eq!(property.access, PropertyAccess::Read);
+    assert_eq!(
+        property.annotations.emits_changed_signal(),
+        Some(EmitsChangedSignal::Invalidates)
+    );
+    let err = property
+        .expect_access(PropertyAccess::Write)
+        .expect_err("`Size` is read-only");
+    assert!(matches!(
+        err,
+        LockstepError::PropertyAccessMismatch {
+            expected: PropertyAccess::Write,
+            actual: PropertyAccess::Read,
+            ..
+        }
+    ));
+
+    let err = index
+        .member_annotations(iface, MsgType::Property, "Sise")
+        .expect_err("Property does not exist");
+    assert!(matches!(err, LockstepError::PropertyNotFound { .. }));
+}
+
+#[test]
+fn test_index_from_dir_filtered() {
+    let xml_path = PathBuf::from("../xml");
+
+    let index = ProtocolIndex::from_dir(&xml_path).unwrap();
+    assert!(matches!(
+        index.locate(MsgType::Signal, "Moved", None),
+        Err(LockstepError::Ambiguous { .. })
+    ));
+
+    let filter = XmlFilter::new().exclude("nested/draft/**");
+    let index = ProtocolIndex::from_dir_filtered(&xml_path, &filter).unwrap();
+    let definition = index.locate(MsgType::Signal, "Moved", None).unwrap();
+    assert_eq!(definition.interface, "org.example.Nested");
+    assert_eq!(
+        definition.path.unwrap(),
+        xml_path.join("nested/test_nested_file.xml")
+    );
+}
+
+#[test]
+fn test_index_from_dirs_reports_provenance() {
+    let vendored = tempfile::tempdir().unwrap();
+    std::fs::write(vendored.path().join("node.xml"), OTHER_NODE_XML).unwrap();
+    // Also defined in the first root, which takes precedence.
+    std::fs::write(
+        vendored.path().join("moved.xml"),
+        NODE_XML.replace("Node", "Nested"),
+    )
+    .unwrap();
+
+    let xml_path = PathBuf::from("../xml");
+    let index = ProtocolIndex::from_dirs([xml_path.as_path(), vendored.path()]).unwrap();
+
+    let definition = index.locate(MsgType::Method, "RequestName", None).unwrap();
+    assert_eq!(definition.root, Some(xml_path.as_path()));
+    assert_eq!(
+        definition.to_string(),
+        "org.example.Node in \"test_definition_file.xml\" of \"../xml\""
+    );
+
+    let definition = index.definition("org.example.Nested").unwrap();
+    assert_eq!(definition.root, Some(xml_path.as_path()));
+    assert_eq!(
+        definition.path.unwrap(),
+        xml_path.join("nested/test_nested_file.xml")
+    );
+
+    let err = index
+        .locate(MsgType::Signal, "AddNode", None)
+        .expect_err("Two interfaces offer `AddNode`");
+    let message = err.to_string();
+    assert!(message.contains("org.example.Node (\"test_definition_file.xml\" of \"../xml\")"));
+    assert!(message.contains(&format!(
+        "org.example.OtherNode (\"node.xml\" of \"{}\")",
+        vendored.path().display()
+    )));
+    let LockstepError::Ambiguous { roots, .. } = err else {
+        panic!("Expected an ambiguity, got {err}");
+    };
+    assert_eq!(
+        roots,
+        [Some(xml_path.clone()), Some(vendored.path().to_owned())]
+    );
+
+    let err = index
+        .signal_body_type("org.example.OtherNode", "RemoveNode", None, BodyShape::Auto)
+        .expect_err("`org.example.OtherNode` has no `RemoveNode` signal");
+    assert_eq!(
+        index.roots(),
+        [xml_path.clone(), vendored.path().to_owned()]
+    );
+    assert!(err.to_string().starts_with(&format!(
+        "Member \"RemoveNode\" not found in \"../xml\", \"{}\".",
+        vendored.path().display()
+    )));
+}
+
+#[test]
+fn test_index_names_unreadable_files() {
+    let missing = PathBuf::from("../xml/missing.xml");
+    let err = ProtocolIndex::from_files([&missing]).expect_err("The file does not exist");
+
+    assert!(matches!(&err, LockstepError::XmlFile { path, .. } if *path == missing));
+    assert!(err.to_string().contains("\"../xml/missing.xml\""), "{err}");
+}
@@ -33,3 +33,96 @@ fn test_property_type_signature() {
     let signature = property_type_signature!("Features");
     assert_eq!(signature, "as");
 }
+
+#[test]
+#[should_panic(expected = "Did you mean \"AddNode\"?")]
+fn test_signal_body_type_signature_suggests_close_names() {
+    let _signature = signal_body_type_signature!("AddNod");
+}
+
+#[test]
+fn test_signal_body_type_signature_by_position() {
+    let signature = signal_body_type_signature!("Alert", "org.example.Node", 2);
+    assert_eq!(signature, "d");
+
+    let signature = signal_body_type_signature!("Alert", "org.example.Node", 0..2);
+    assert_eq!(signature, "bs");
+}
+
+#[test]
+fn test_method_args_signature_by_names() {
+    let signature = method_args_signature!("RequestName", "org.example.Node", ["orange", "apple"]);
+    assert_eq!(signature, "us");
+}
+
+#[test]
+fn test_signal_body_type_signature_with_shape() {
+    use zbus_lockstep::BodyShape;
+
+    let signature =
+        signal_body_type_signature!("U32AsEnum", "org.example.Node", None, BodyShape::Struct);
+    assert_eq!(signature.to_string(), "(u)");
+
+    let signature = signal_body_type_signature!(
+        member: "U32AsEnum",
+        interface: "org.example.Node",
+        argument: None,
+        shape: BodyShape::Flattened
+    );
+    assert_eq!(signature.to_string(), "u");
+}
+
+#[test]
+fn test_members_without_arguments_are_unit() {
+    use zvariant::Type;
+
+    let signature = signal_body_type_signature!("Reset");
+    assert_eq!(&signature, <()>::SIGNATURE);
+
+    let signature = method_args_signature!("Ping");
+    assert_eq!(&signature, <()>::SIGNATURE);
+
+    let signature = method_return_signature!("Ping");
+    assert_eq!(&signature, <()>::SIGNATURE);
+}
+
+#[test]
+fn test_property_type_signature_with_access() {
+    use zbus_lockstep::PropertyAccess;
+
+    let signature = property_type_signature!(member: "Features", access: PropertyAccess::Read);
+    assert_eq!(signature, "as");
+
+    let signature = property_type_signature!(
+        member: "Features",
+        interface: "org.example.Node",
+        access: PropertyAccess::Read
+    );
+    assert_eq!(signature, "as");
+}
+
+#[test]
+#[should_panic(expected = "Property \"Features\" has access \"read\", expected \"readwrite\".")]
+fn test_property_type_signature_with_wrong_access() {
+    use zbus_lockstep::PropertyAccess;
+
+    let _signature =
+        property_type_signature!(member: "Features", access: PropertyAccess::ReadWrite);
+}
+
+#[test]
+#[should_panic(expected = "field 1 (`path`): expected `o`, found `s`")]
+fn test_assert_signature_eq_reports_field_paths() {
+    use zbus_lockstep::assert_signature_eq;
+    use zvariant::Type;
+
+    #[derive(Type)]
+    #[allow(dead_code)]
+    struct Node {
+        name: String,
+        path: String,
+    }
+
+    let signature = signal_body_type_signature!("AddNode");
+    assert_signature_eq!(signature, Node::SIGNATURE, &["name", "path"]);
+}